@@ -0,0 +1,229 @@
+use crate::error::ApiError;
+use crate::types::BallchasingReplay;
+
+use axum::{
+    body::Bytes,
+    extract::{multipart::MultipartError, Multipart},
+};
+use schemars::JsonSchema;
+use serde::Serialize;
+use std::collections::BTreeMap;
+use std::io::{Cursor, Read};
+use ts_rs::TS;
+
+/// Outcome of one replay of a `/parse/batch` upload
+#[derive(Debug, Serialize, TS, JsonSchema)]
+#[serde(tag = "status", rename_all = "snake_case")]
+pub enum BatchResult {
+    Ok {
+        replay: Box<BallchasingReplay>,
+    },
+    Error {
+        error: String,
+        /// Same machine code as the single-file routes
+        code: String,
+    },
+}
+
+impl From<Result<BallchasingReplay, ApiError>> for BatchResult {
+    fn from(result: Result<BallchasingReplay, ApiError>) -> Self {
+        match result {
+            Ok(replay) => BatchResult::Ok {
+                replay: Box::new(replay),
+            },
+            Err(e) => BatchResult::Error {
+                error: e.to_string(),
+                code: e.code().to_string(),
+            },
+        }
+    }
+}
+
+/// Replays of one batch when `MAX_BATCH_FILES` is not set
+const DEFAULT_MAX_BATCH_FILES: usize = 100;
+
+/// Request body and combined replay size of one batch when `MAX_BATCH_UPLOAD_MB` is not set
+const DEFAULT_MAX_BATCH_UPLOAD_MB: usize = 200;
+
+/// A replay of the batch, or the reason it can't be parsed before decoding starts
+pub type BatchFile = (String, Result<Bytes, ApiError>);
+
+/// How much a single `/parse/batch` request may hold
+#[derive(Clone, Copy)]
+pub struct BatchLimits {
+    /// Size of each replay, as for the single-file routes
+    pub max_file_size: usize,
+    /// Replays in the form and in its zip archives together
+    pub max_files: usize,
+    /// Size of the request body, and of every replay together once unzipped
+    pub max_bytes: usize,
+}
+
+impl BatchLimits {
+    /// Configure from `MAX_BATCH_FILES` and `MAX_BATCH_UPLOAD_MB`
+    pub fn from_env(max_file_size: usize) -> Self {
+        let env = |key: &str| std::env::var(key).ok().and_then(|v| v.parse().ok());
+        Self {
+            max_file_size,
+            max_files: env("MAX_BATCH_FILES").unwrap_or(DEFAULT_MAX_BATCH_FILES),
+            max_bytes: env("MAX_BATCH_UPLOAD_MB").unwrap_or(DEFAULT_MAX_BATCH_UPLOAD_MB)
+                * 1024
+                * 1024,
+        }
+    }
+}
+
+/// Replays and bytes a batch has taken so far
+#[derive(Default, Clone, Copy)]
+struct Usage {
+    files: usize,
+    bytes: usize,
+}
+
+impl Usage {
+    /// Count one more replay of `size` bytes, failing once the batch is over its limits
+    fn add(&mut self, size: usize, limits: &BatchLimits) -> Result<(), ApiError> {
+        self.files += 1;
+        self.bytes += size;
+        if self.files > limits.max_files {
+            Err(ApiError::TooManyFiles {
+                limit: limits.max_files,
+            })
+        } else if self.bytes > limits.max_bytes {
+            Err(ApiError::TooLarge {
+                limit: limits.max_bytes,
+            })
+        } else {
+            Ok(())
+        }
+    }
+}
+
+/// Every file of the form, keyed by file name; zip archives are replaced by the
+/// `.replay` files they contain
+pub async fn read_batch_upload(
+    mut multipart: Multipart,
+    limits: BatchLimits,
+) -> Result<Vec<BatchFile>, ApiError> {
+    let mut files = Vec::new();
+    let mut usage = Usage::default();
+
+    // The body limit of this route is `max_bytes`, not `MAX_UPLOAD_MB`
+    let body_error = |e: MultipartError| match ApiError::from(e) {
+        ApiError::TooLarge { .. } => ApiError::TooLarge {
+            limit: limits.max_bytes,
+        },
+        e => e,
+    };
+
+    while let Some(field) = multipart.next_field().await.map_err(body_error)? {
+        let name = field
+            .file_name()
+            .or(field.name())
+            .unwrap_or("replay")
+            .to_string();
+        let data = field.bytes().await.map_err(body_error)?;
+
+        if is_zip(&name, &data) {
+            let (entries, used) = tokio::task::spawn_blocking(move || unzip(&data, &limits, usage))
+                .await
+                .map_err(|e| ApiError::Internal(e.to_string()))??;
+            files.extend(entries);
+            usage = used;
+        } else if data.is_empty() {
+            files.push((name, Err(ApiError::EmptyBody)));
+        } else {
+            usage.add(data.len(), &limits)?;
+            if data.len() > limits.max_file_size {
+                let limit = limits.max_file_size;
+                files.push((name, Err(ApiError::TooLarge { limit })));
+            } else {
+                files.push((name, Ok(data)));
+            }
+        }
+    }
+
+    if files.is_empty() {
+        return Err(ApiError::EmptyBody);
+    }
+    Ok(unique_names(files))
+}
+
+fn is_zip(name: &str, data: &[u8]) -> bool {
+    name.to_lowercase().ends_with(".zip") || data.starts_with(b"PK\x03\x04")
+}
+
+/// `.replay` entries of a zip archive; entries bigger than `max_file_size` once
+/// decompressed are reported instead of extracted, and the whole archive fails once the
+/// batch goes over its replay count or combined size
+fn unzip(
+    data: &[u8],
+    limits: &BatchLimits,
+    mut usage: Usage,
+) -> Result<(Vec<BatchFile>, Usage), ApiError> {
+    let max_file_size = limits.max_file_size;
+    let mut archive =
+        zip::ZipArchive::new(Cursor::new(data)).map_err(|e| ApiError::BadArchive(e.to_string()))?;
+    let mut files = Vec::new();
+
+    for i in 0..archive.len() {
+        let mut entry = archive
+            .by_index(i)
+            .map_err(|e| ApiError::BadArchive(e.to_string()))?;
+        let name = entry
+            .name()
+            .map_err(|e| ApiError::BadArchive(e.to_string()))?
+            .to_string();
+        // Skip folders and the resource forks macOS adds to archives
+        if !entry.is_file()
+            || !name.to_lowercase().ends_with(".replay")
+            || name.starts_with("__MACOSX/")
+        {
+            continue;
+        }
+
+        // The declared size can lie, so the read itself is capped too
+        let too_large = ApiError::TooLarge {
+            limit: max_file_size,
+        };
+        if entry.size() > max_file_size as u64 {
+            usage.add(0, limits)?;
+            files.push((name, Err(too_large)));
+            continue;
+        }
+
+        let mut content = Vec::with_capacity(entry.size() as usize);
+        let result = match entry
+            .by_ref()
+            .take(max_file_size as u64 + 1)
+            .read_to_end(&mut content)
+        {
+            Ok(0) => Err(ApiError::EmptyBody),
+            Ok(read) if read > max_file_size => Err(too_large),
+            Ok(_) => Ok(Bytes::from(content)),
+            Err(e) => Err(ApiError::BadArchive(format!("{}: {}", name, e))),
+        };
+        usage.add(result.as_ref().map_or(0, Bytes::len), limits)?;
+        files.push((name, result));
+    }
+
+    Ok((files, usage))
+}
+
+/// Suffix repeated names with ` (2)`, ` (3)`, ... so no result is overwritten
+fn unique_names(files: Vec<BatchFile>) -> Vec<BatchFile> {
+    let mut seen: BTreeMap<String, u32> = BTreeMap::new();
+
+    files
+        .into_iter()
+        .map(|(name, data)| {
+            let count = seen.entry(name.clone()).or_default();
+            *count += 1;
+            if *count == 1 {
+                (name, data)
+            } else {
+                (format!("{} ({})", name, count), data)
+            }
+        })
+        .collect()
+}
@@ -0,0 +1,285 @@
+use crate::catalog::Catalog;
+use crate::dates::source_timezone;
+use crate::error::ApiError;
+use crate::parser::parse_to_ballchasing;
+use crate::stats::STATS_VERSION;
+use crate::types::BallchasingReplay;
+
+use axum::body::Bytes;
+use boxcars::{NetworkParse, ParserBuilder};
+use schemars::JsonSchema;
+use serde::Serialize;
+use sha2::{Digest, Sha256};
+use std::collections::{HashMap, VecDeque};
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::{Arc, Mutex, MutexGuard};
+
+/// Parses kept in memory when `CACHE_MAX_ENTRIES` is not set
+const DEFAULT_MAX_ENTRIES: usize = 256;
+
+/// Subdirectory of `CACHE_DIR` the parses are stored in
+const PARSES_DIR: &str = "parses";
+
+/// Parsed replays by `{version}-{content hash}`; a parse is reused until the replay bytes, the crate
+/// version or `STATS_VERSION` change
+pub struct ParseCache {
+    entries: Mutex<Entries>,
+    max_entries: usize,
+    /// Optional backing store that outlives restarts (`CACHE_DIR/parses`)
+    dir: Option<PathBuf>,
+    hits: AtomicU64,
+    misses: AtomicU64,
+}
+
+/// Cached parses, evicted oldest first
+#[derive(Default)]
+struct Entries {
+    replays: HashMap<String, Arc<BallchasingReplay>>,
+    order: VecDeque<String>,
+}
+
+/// Counters reported by `GET /admin/cache`
+#[derive(Debug, Serialize, JsonSchema)]
+pub struct CacheStats {
+    pub version: String,
+    pub entries: usize,
+    pub max_entries: usize,
+    pub dir: Option<String>,
+    pub hits: u64,
+    pub misses: u64,
+}
+
+/// Result of a cache lookup: the cached replay, or the parse to run on the pool
+pub enum Lookup {
+    Hit(Box<BallchasingReplay>),
+    Miss(Box<dyn FnOnce() -> Result<BallchasingReplay, ApiError> + Send>),
+}
+
+/// Parser and stats version every key is prefixed with, plus `REPLAY_TIMEZONE` since
+/// replay dates depend on it
+pub fn version() -> String {
+    let version = format!("{}-stats{}", env!("CARGO_PKG_VERSION"), STATS_VERSION);
+    match source_timezone() {
+        Some(tz) => format!("{}-{}", version, tz.name().replace('/', "_")),
+        None => version,
+    }
+}
+
+impl ParseCache {
+    /// Configure from `CACHE_MAX_ENTRIES` and `CACHE_DIR`; disk entries of other
+    /// versions are removed, and files that aren't cache entries are left alone
+    pub fn from_env() -> Self {
+        let max_entries = std::env::var("CACHE_MAX_ENTRIES")
+            .ok()
+            .and_then(|n| n.parse().ok())
+            .unwrap_or(DEFAULT_MAX_ENTRIES);
+
+        let dir = std::env::var("CACHE_DIR")
+            .ok()
+            .map(|dir| PathBuf::from(dir).join(PARSES_DIR));
+        if let Some(dir) = &dir {
+            match std::fs::create_dir_all(dir) {
+                Ok(()) => {
+                    let stale = remove_files(dir, |name| {
+                        entry_version(name).is_some_and(|v| v != version())
+                    });
+                    println!(
+                        "💾 Caching parses in {}, {} stale entries removed",
+                        dir.display(),
+                        stale
+                    );
+                }
+                Err(e) => eprintln!("⚠️ Failed to create {}: {}", dir.display(), e),
+            }
+        }
+
+        Self {
+            entries: Mutex::new(Entries::default()),
+            max_entries,
+            dir,
+            hits: AtomicU64::new(0),
+            misses: AtomicU64::new(0),
+        }
+    }
+
+    /// Look `data` up in memory; on a miss, the returned parse checks the disk store
+    /// before decoding the replay, and caches what it produces. The upload is hashed on
+    /// the blocking pool, since a batch can be hundreds of megabytes
+    pub async fn lookup(
+        self: &Arc<Self>,
+        data: Bytes,
+        catalog: Arc<Catalog>,
+    ) -> Result<Lookup, ApiError> {
+        let (data, hash) = tokio::task::spawn_blocking(move || {
+            let hash = content_hash(&data);
+            (data, hash)
+        })
+        .await
+        .map_err(|e| ApiError::Internal(e.to_string()))?;
+        let key = format!("{}-{}", version(), hash);
+
+        if let Some(replay) = self.entries().replays.get(&key) {
+            self.hits.fetch_add(1, Ordering::Relaxed);
+            return Ok(Lookup::Hit(Box::new(BallchasingReplay::clone(replay))));
+        }
+
+        let cache = self.clone();
+        Ok(Lookup::Miss(Box::new(move || {
+            if let Some(replay) = cache.load(&key) {
+                cache.hits.fetch_add(1, Ordering::Relaxed);
+                cache.remember(key, &replay);
+                return Ok(replay);
+            }
+
+            cache.misses.fetch_add(1, Ordering::Relaxed);
+            let replay = ParserBuilder::new(&data)
+                .with_network_parse(NetworkParse::IgnoreOnError)
+                .parse()?;
+            let replay = parse_to_ballchasing(&replay, &catalog, &hash);
+            cache.store(&key, &replay);
+            cache.remember(key, &replay);
+            Ok(replay)
+        })))
+    }
+
+    /// Drop every cached parse, in memory and on disk; returns how many were in memory
+    pub fn purge(&self) -> usize {
+        let purged = {
+            let mut entries = self.entries();
+            let count = entries.replays.len();
+            *entries = Entries::default();
+            count
+        };
+        if let Some(dir) = &self.dir {
+            remove_files(dir, |name| entry_version(name).is_some());
+        }
+        purged
+    }
+
+    pub fn stats(&self) -> CacheStats {
+        CacheStats {
+            version: version(),
+            entries: self.entries().replays.len(),
+            max_entries: self.max_entries,
+            dir: self.dir.as_ref().map(|d| d.display().to_string()),
+            hits: self.hits.load(Ordering::Relaxed),
+            misses: self.misses.load(Ordering::Relaxed),
+        }
+    }
+
+    fn remember(&self, key: String, replay: &BallchasingReplay) {
+        if self.max_entries == 0 {
+            return;
+        }
+
+        let mut entries = self.entries();
+        if entries
+            .replays
+            .insert(key.clone(), Arc::new(replay.clone()))
+            .is_none()
+        {
+            entries.order.push_back(key);
+        }
+        while entries.order.len() > self.max_entries {
+            if let Some(oldest) = entries.order.pop_front() {
+                entries.replays.remove(&oldest);
+            }
+        }
+    }
+
+    fn load(&self, key: &str) -> Option<BallchasingReplay> {
+        let content = std::fs::read(self.path(key)?).ok()?;
+        serde_json::from_slice(&content).ok()
+    }
+
+    fn store(&self, key: &str, replay: &BallchasingReplay) {
+        let Some(path) = self.path(key) else {
+            return;
+        };
+        let written = serde_json::to_vec(replay)
+            .map_err(|e| e.to_string())
+            .and_then(|content| std::fs::write(&path, content).map_err(|e| e.to_string()));
+        if let Err(e) = written {
+            eprintln!("⚠️ Failed to cache {}: {}", path.display(), e);
+        }
+    }
+
+    fn path(&self, key: &str) -> Option<PathBuf> {
+        self.dir
+            .as_ref()
+            .map(|dir| dir.join(format!("{}.json", key)))
+    }
+
+    fn entries(&self) -> MutexGuard<'_, Entries> {
+        match self.entries.lock() {
+            Ok(entries) => entries,
+            Err(poisoned) => poisoned.into_inner(),
+        }
+    }
+}
+
+/// Hex SHA-256 of a replay file
+pub fn content_hash(data: &[u8]) -> String {
+    Sha256::digest(data)
+        .iter()
+        .map(|b| format!("{:02x}", b))
+        .collect()
+}
+
+/// Version of a `{version}-{content hash}.json` cache entry, or `None` for any other file
+fn entry_version(name: &str) -> Option<&str> {
+    let (version, hash) = name.strip_suffix(".json")?.rsplit_once('-')?;
+    let is_hash = hash.len() == 64 && hash.bytes().all(|b| b.is_ascii_hexdigit());
+    (is_hash && !version.is_empty()).then_some(version)
+}
+
+/// Remove the files of `dir` whose name matches; returns how many were removed
+fn remove_files(dir: &Path, matches: impl Fn(&str) -> bool) -> usize {
+    let Ok(entries) = std::fs::read_dir(dir) else {
+        return 0;
+    };
+    entries
+        .flatten()
+        .filter(|entry| entry.file_name().to_str().is_some_and(&matches))
+        .filter(|entry| std::fs::remove_file(entry.path()).is_ok())
+        .count()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn entries_are_told_apart_by_exact_version() {
+        let hash = content_hash(b"replay");
+        let current = version();
+        let entry = |version: &str| format!("{}-{}.json", version, hash);
+
+        assert_eq!(entry_version(&entry(&current)), Some(current.as_str()));
+        assert_ne!(
+            entry_version(&entry(&format!("{}0", current))),
+            Some(current.as_str())
+        );
+        assert_ne!(
+            entry_version(&entry(&format!("{}-Europe_Berlin", current))),
+            Some(current.as_str())
+        );
+    }
+
+    #[test]
+    fn other_files_are_not_entries() {
+        let hash = content_hash(b"replay");
+        for name in [
+            "products.json".to_string(),
+            "6f1c2a0e-5b7d-4c53-9a8e-2d41f0b7c3e9.json".to_string(),
+            "6f1c2a0e-5b7d-4c53-9a8e-2d41f0b7c3e9.result.json".to_string(),
+            "matches.jsonl".to_string(),
+            format!("{}.json", hash),
+            format!("{}-{}.json", version(), &hash[1..]),
+            format!("{}-{}.json", version(), "z".repeat(64)),
+        ] {
+            assert_eq!(entry_version(&name), None, "{}", name);
+        }
+    }
+}
@@ -0,0 +1,204 @@
+use crate::types::{BallchasingReplay, LoadoutItem};
+
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, HashMap};
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, Mutex, RwLock};
+
+/// Catalog used when `PRODUCT_CATALOG` is not set
+const DEFAULT_CATALOG_PATH: &str = "data/products.json";
+
+/// Copy of the default catalog, used when the file can't be read at startup
+const BUNDLED_CATALOG: &str = include_str!("../data/products.json");
+
+/// A product that can be equipped in a loadout slot
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Product {
+    pub id: u32,
+    /// Loadout slot (`body`, `decal`, `wheels`, `boost`, `goal_explosion`, ...)
+    pub slot: String,
+    pub name: String,
+}
+
+/// Product names by ID
+#[derive(Debug, Default)]
+pub struct Catalog {
+    products: HashMap<u32, Product>,
+}
+
+impl Catalog {
+    /// Load a catalog from a `.json` array of products or a `.csv` file with
+    /// `id,slot,name` rows
+    pub fn load(path: &Path) -> Result<Self, String> {
+        let content = std::fs::read_to_string(path)
+            .map_err(|e| format!("failed to read {}: {}", path.display(), e))?;
+
+        let products = if path.extension().is_some_and(|ext| ext == "csv") {
+            parse_csv(&content)?
+        } else {
+            serde_json::from_str(&content)
+                .map_err(|e| format!("failed to parse {}: {}", path.display(), e))?
+        };
+
+        Ok(Self::from_products(products))
+    }
+
+    fn from_products(products: Vec<Product>) -> Self {
+        Self {
+            products: products.into_iter().map(|p| (p.id, p)).collect(),
+        }
+    }
+
+    pub fn name(&self, id: u32) -> Option<&str> {
+        self.products.get(&id).map(|p| p.name.as_str())
+    }
+
+    pub fn len(&self) -> usize {
+        self.products.len()
+    }
+}
+
+/// Parse `id,slot,name` rows; the name is the last column so it may contain commas
+fn parse_csv(content: &str) -> Result<Vec<Product>, String> {
+    content
+        .lines()
+        .enumerate()
+        .filter(|(_, line)| !line.trim().is_empty())
+        // Skip the header row
+        .filter(|(i, line)| !(*i == 0 && line.starts_with("id,")))
+        .map(|(i, line)| {
+            let mut columns = line.splitn(3, ',');
+            let (Some(id), Some(slot), Some(name)) =
+                (columns.next(), columns.next(), columns.next())
+            else {
+                return Err(format!("line {}: expected id,slot,name", i + 1));
+            };
+            let id = id
+                .trim()
+                .parse()
+                .map_err(|_| format!("line {}: invalid product ID {:?}", i + 1, id))?;
+            Ok(Product {
+                id,
+                slot: slot.trim().to_string(),
+                name: name.trim().trim_matches('"').to_string(),
+            })
+        })
+        .collect()
+}
+
+/// A product ID seen in a replay but missing from the catalog
+#[derive(Debug, Clone, Serialize, JsonSchema)]
+pub struct UnknownProduct {
+    pub id: u32,
+    pub slot: String,
+    /// Number of players seen with it
+    pub count: u32,
+}
+
+/// The live catalog, swappable at runtime, plus the product IDs it failed to name
+pub struct CatalogStore {
+    path: PathBuf,
+    current: RwLock<Arc<Catalog>>,
+    unknown: Mutex<BTreeMap<u32, UnknownProduct>>,
+}
+
+impl CatalogStore {
+    /// Load the catalog from `PRODUCT_CATALOG`, falling back to the bundled copy
+    pub fn from_env() -> Self {
+        let path = PathBuf::from(
+            std::env::var("PRODUCT_CATALOG").unwrap_or_else(|_| DEFAULT_CATALOG_PATH.to_string()),
+        );
+
+        let catalog = Catalog::load(&path).unwrap_or_else(|e| {
+            eprintln!("⚠️ Using bundled product catalog: {}", e);
+            let products = serde_json::from_str(BUNDLED_CATALOG).unwrap_or_default();
+            Catalog::from_products(products)
+        });
+        println!("📦 Loaded {} products", catalog.len());
+
+        Self {
+            path,
+            current: RwLock::new(Arc::new(catalog)),
+            unknown: Mutex::new(BTreeMap::new()),
+        }
+    }
+
+    /// Snapshot of the current catalog
+    pub fn get(&self) -> Arc<Catalog> {
+        match self.current.read() {
+            Ok(catalog) => catalog.clone(),
+            Err(poisoned) => poisoned.into_inner().clone(),
+        }
+    }
+
+    /// Re-read the catalog file; the current catalog is kept if it fails
+    pub fn reload(&self) -> Result<usize, String> {
+        let catalog = Catalog::load(&self.path)?;
+        let count = catalog.len();
+
+        let mut current = match self.current.write() {
+            Ok(current) => current,
+            Err(poisoned) => poisoned.into_inner(),
+        };
+        *current = Arc::new(catalog);
+
+        // Products the new catalog knows about are no longer unknown
+        self.unknown_products()
+            .retain(|id, _| current.name(*id).is_some());
+        Ok(count)
+    }
+
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+
+    /// Remember every loadout product the parsed replay couldn't name
+    pub fn record_unknown(&self, replay: &BallchasingReplay) {
+        let mut unknown = self.unknown_products();
+
+        for player in replay.blue.players.iter().chain(&replay.orange.players) {
+            let l = &player.loadout;
+            let items = [
+                ("body", l.body.as_ref()),
+                ("decal", l.decal.as_ref()),
+                ("wheels", l.wheels.as_ref()),
+                ("boost", l.boost.as_ref()),
+                ("antenna", l.antenna.as_ref()),
+                ("topper", l.topper.as_ref()),
+                ("engine_audio", l.engine_audio.as_ref()),
+                ("trail", l.trail.as_ref()),
+                ("goal_explosion", l.goal_explosion.as_ref()),
+                ("banner", l.banner.as_ref()),
+                ("paint_finish", l.paint.as_ref().map(|p| &p.primary_finish)),
+                ("paint_finish", l.paint.as_ref().map(|p| &p.accent_finish)),
+            ];
+
+            for (slot, item) in items {
+                let Some(LoadoutItem { id, name: None, .. }) = item else {
+                    continue;
+                };
+                unknown
+                    .entry(*id)
+                    .or_insert_with(|| UnknownProduct {
+                        id: *id,
+                        slot: slot.to_string(),
+                        count: 0,
+                    })
+                    .count += 1;
+            }
+        }
+    }
+
+    /// Unknown product IDs seen since startup, by ID
+    pub fn unknown(&self) -> Vec<UnknownProduct> {
+        self.unknown_products().values().cloned().collect()
+    }
+
+    fn unknown_products(&self) -> std::sync::MutexGuard<'_, BTreeMap<u32, UnknownProduct>> {
+        match self.unknown.lock() {
+            Ok(unknown) => unknown,
+            Err(poisoned) => poisoned.into_inner(),
+        }
+    }
+}
@@ -0,0 +1,239 @@
+use chrono::{DateTime, FixedOffset, NaiveDateTime, TimeDelta, TimeZone, Utc};
+use chrono_tz::Tz;
+use std::sync::OnceLock;
+
+/// Layouts of the header `Date`, newest first (`2025-01-14 21-05-33`, `2016-09-10:19-42`)
+const DATE_FORMATS: [&str; 2] = ["%Y-%m-%d %H-%M-%S", "%Y-%m-%d:%H-%M"];
+
+/// Offsets are inferred in steps of 15 minutes, the finest real timezones use
+const OFFSET_STEP_SECONDS: i64 = 15 * 60;
+
+/// Largest offset of a real timezone (UTC+14)
+const MAX_OFFSET_SECONDS: i64 = 14 * 3600;
+
+/// Offsets in use that aren't whole hours, in minutes (Marquesas, Newfoundland, Iran,
+/// Afghanistan, India, Nepal, Myanmar, Eucla, central Australia, Lord Howe, Chatham)
+const PARTIAL_HOUR_OFFSETS: [i64; 13] = [
+    -570, -210, -150, 210, 270, 330, 345, 390, 525, 570, 630, 765, 825,
+];
+
+/// How long after the final whistle the replay may be saved for a configured timezone
+/// to still agree with `MatchStartEpoch`
+const SAVE_DELAY_SECONDS: i64 = 30 * 60;
+
+static SOURCE_TIMEZONE: OnceLock<Option<Tz>> = OnceLock::new();
+
+/// The header `Date` as RFC 3339
+#[derive(Debug)]
+pub struct ReplayDate {
+    pub date: String,
+    /// Whether the offset is known; otherwise `date` is the header clock labelled as UTC
+    pub has_timezone: bool,
+}
+
+/// Timezone header dates are written in, from `REPLAY_TIMEZONE` (an IANA name such as
+/// `Europe/Berlin`); unset or invalid means the offset is inferred from `MatchStartEpoch`
+pub fn source_timezone() -> Option<Tz> {
+    *SOURCE_TIMEZONE.get_or_init(|| {
+        let name = std::env::var("REPLAY_TIMEZONE").ok()?;
+        match name.parse::<Tz>() {
+            Ok(tz) => Some(tz),
+            Err(e) => {
+                eprintln!("⚠️ Ignoring REPLAY_TIMEZONE {:?}: {}", name, e);
+                None
+            }
+        }
+    })
+}
+
+/// Parse the header `Date`, which is the local time the replay was saved at. The
+/// `timezone` (usually `source_timezone()`) is used unless it disagrees with
+/// `MatchStartEpoch` plus the match duration and the epoch implies a real offset, in
+/// which case that offset wins. `None` when the date doesn't match a known layout
+pub fn parse_replay_date(
+    raw: &str,
+    match_start_epoch: Option<i64>,
+    duration: f64,
+    timezone: Option<Tz>,
+) -> Option<ReplayDate> {
+    let local = DATE_FORMATS
+        .iter()
+        .find_map(|format| NaiveDateTime::parse_from_str(raw, format).ok())?;
+    // When the match ended, which is when the replay is saved
+    let ended = match_start_epoch
+        .and_then(|epoch| DateTime::from_timestamp(epoch, 0))
+        .zip(TimeDelta::try_seconds(duration as i64))
+        .and_then(|(start, duration)| start.checked_add_signed(duration));
+
+    let configured = timezone
+        .and_then(|tz| tz.from_local_datetime(&local).earliest())
+        .map(|date| date.fixed_offset());
+    let inferred = ended.and_then(|ended| infer_offset(local, ended));
+
+    let date = match (configured, inferred) {
+        (Some(date), _) if ended.is_some_and(|ended| agrees(date, ended)) => Some(date),
+        (_, Some(offset)) => offset.from_local_datetime(&local).single(),
+        (configured, None) => configured,
+    };
+
+    Some(match date {
+        Some(date) => ReplayDate {
+            date: date.to_rfc3339(),
+            has_timezone: true,
+        },
+        None => ReplayDate {
+            date: local.and_utc().to_rfc3339(),
+            has_timezone: false,
+        },
+    })
+}
+
+/// Offset between the local save time and the UTC end of the match, rounded to the
+/// nearest 15 minutes; `None` unless some timezone actually uses it
+fn infer_offset(local: NaiveDateTime, ended: DateTime<Utc>) -> Option<FixedOffset> {
+    let seconds = (local - ended.naive_utc()).num_seconds();
+    let offset = (seconds as f64 / OFFSET_STEP_SECONDS as f64).round() as i64 * OFFSET_STEP_SECONDS;
+    let is_real = offset % 3600 == 0 || PARTIAL_HOUR_OFFSETS.contains(&(offset / 60));
+    if offset.abs() > MAX_OFFSET_SECONDS || !is_real {
+        return None;
+    }
+    FixedOffset::east_opt(offset as i32)
+}
+
+/// Whether a save time lies between the end of the match and `SAVE_DELAY_SECONDS` later,
+/// with a minute of leeway for clock drift
+fn agrees(date: DateTime<FixedOffset>, ended: DateTime<Utc>) -> bool {
+    let delay = (date.with_timezone(&Utc) - ended).num_seconds();
+    (-60..=SAVE_DELAY_SECONDS).contains(&delay)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// 2025-01-14 20:00:00 UTC
+    const START: i64 = 1736884800;
+
+    /// A five minute match, so it ended at 20:05:00 UTC
+    const DURATION: f64 = 300.0;
+
+    fn parse(raw: &str, epoch: Option<i64>, timezone: Option<Tz>) -> (String, bool) {
+        let date = parse_replay_date(raw, epoch, DURATION, timezone).unwrap();
+        (date.date, date.has_timezone)
+    }
+
+    fn ended() -> DateTime<Utc> {
+        DateTime::from_timestamp(START, 0).unwrap() + TimeDelta::seconds(DURATION as i64)
+    }
+
+    fn local(raw: &str) -> NaiveDateTime {
+        NaiveDateTime::parse_from_str(raw, DATE_FORMATS[0]).unwrap()
+    }
+
+    #[test]
+    fn offset_is_inferred_from_match_start() {
+        assert_eq!(
+            parse("2025-01-14 21-05-33", Some(START), None),
+            ("2025-01-14T21:05:33+01:00".into(), true)
+        );
+    }
+
+    #[test]
+    fn configured_timezone_that_agrees_is_used() {
+        // Saved 29 minutes after the end: UTC agrees, although the nearest step is +00:30
+        assert_eq!(
+            parse("2025-01-14 20-34-00", Some(START), Some(Tz::UTC)),
+            ("2025-01-14T20:34:00+00:00".into(), true)
+        );
+    }
+
+    #[test]
+    fn configured_timezone_that_disagrees_loses_to_match_start() {
+        assert_eq!(
+            parse(
+                "2025-01-14 21-05-33",
+                Some(START),
+                Some(Tz::America__New_York)
+            ),
+            ("2025-01-14T21:05:33+01:00".into(), true)
+        );
+    }
+
+    #[test]
+    fn implausible_offsets_are_not_inferred() {
+        // 31 minutes after the end is outside the window, but +00:30 isn't a real offset
+        assert_eq!(
+            parse("2025-01-14 20-36-00", Some(START), Some(Tz::UTC)),
+            ("2025-01-14T20:36:00+00:00".into(), true)
+        );
+        assert_eq!(
+            parse("2025-01-14 20-36-00", Some(START), None),
+            ("2025-01-14T20:36:00+00:00".into(), false)
+        );
+    }
+
+    #[test]
+    fn durations_out_of_range_are_ignored() {
+        for duration in [f64::INFINITY, 1e300] {
+            let date = parse_replay_date("2025-01-14 21-05-33", Some(START), duration, None);
+            assert!(!date.unwrap().has_timezone, "{}", duration);
+        }
+        let date = parse_replay_date("2025-01-14 21-05-33", Some(i64::MAX), DURATION, None);
+        assert!(!date.unwrap().has_timezone);
+    }
+
+    #[test]
+    fn without_match_start() {
+        assert_eq!(
+            parse("2025-01-14 21-05-33", None, None),
+            ("2025-01-14T21:05:33+00:00".into(), false)
+        );
+        assert_eq!(
+            parse("2025-07-14 21-05-33", None, Some(Tz::Europe__Berlin)),
+            ("2025-07-14T21:05:33+02:00".into(), true)
+        );
+    }
+
+    #[test]
+    fn old_layout() {
+        assert_eq!(
+            parse("2016-09-10:19-42", None, None),
+            ("2016-09-10T19:42:00+00:00".into(), false)
+        );
+    }
+
+    #[test]
+    fn unparseable_date() {
+        assert!(parse_replay_date("", Some(START), DURATION, None).is_none());
+        assert!(parse_replay_date("14/01/2025 21:05", None, DURATION, None).is_none());
+    }
+
+    #[test]
+    fn agreement_window() {
+        let end = ended();
+        let saved = |seconds: i64| (end + TimeDelta::seconds(seconds)).fixed_offset();
+        assert!(agrees(saved(-60), end));
+        assert!(!agrees(saved(-61), end));
+        assert!(agrees(saved(SAVE_DELAY_SECONDS), end));
+        assert!(!agrees(saved(SAVE_DELAY_SECONDS + 1), end));
+    }
+
+    #[test]
+    fn offsets_round_to_15_minutes() {
+        let offset = |raw: &str| infer_offset(local(raw), ended()).map(|o| o.local_minus_utc());
+        // Nepal, UTC+05:45, saved a few minutes after the end
+        assert_eq!(offset("2025-01-15 01-57-29"), Some(5 * 3600 + 45 * 60));
+        assert_eq!(offset("2025-01-15 01-57-31"), Some(6 * 3600));
+        assert_eq!(offset("2025-01-14 16-32-00"), Some(-(3 * 3600 + 30 * 60)));
+        // Nowhere is UTC+01:15
+        assert_eq!(offset("2025-01-14 21-20-00"), None);
+    }
+
+    #[test]
+    fn offsets_beyond_14_hours_are_rejected() {
+        let offset = |raw: &str| infer_offset(local(raw), ended()).map(|o| o.local_minus_utc());
+        assert_eq!(offset("2025-01-15 10-05-00"), Some(14 * 3600));
+        assert_eq!(offset("2025-01-15 10-20-00"), None);
+        assert_eq!(offset("2025-01-14 05-50-00"), None);
+    }
+}
@@ -0,0 +1,231 @@
+use crate::header::HeaderError;
+
+use axum::{
+    extract::multipart::{MultipartError, MultipartRejection},
+    http::{header::RETRY_AFTER, HeaderValue, StatusCode},
+    response::{IntoResponse, Response},
+    Json,
+};
+use boxcars::ParseError;
+use serde_json::json;
+use std::fmt;
+
+/// Everything a request can fail with; rendered as
+/// `{ "error": message, "code": machine_code, "field"?: header_property }`
+#[derive(Debug)]
+pub enum ApiError {
+    /// The request isn't `multipart/form-data`
+    NotMultipart,
+    /// The multipart body couldn't be read
+    BadMultipart(String),
+    /// No replay file, or a zero-byte one
+    EmptyBody,
+    /// The form has fields, but none named `file`
+    WrongField {
+        found: Vec<String>,
+    },
+    /// A batch upload's zip archive can't be read
+    BadArchive(String),
+    /// A job's callback URL isn't an http(s) URL, or its host isn't allowed
+    InvalidCallback(String),
+    /// The upload exceeds `MAX_UPLOAD_MB`, or a batch `MAX_BATCH_UPLOAD_MB`
+    TooLarge {
+        limit: usize,
+    },
+    /// A batch holds more replays than `MAX_BATCH_FILES` or the parse queue allows
+    TooManyFiles {
+        limit: usize,
+    },
+    /// The replay checksum doesn't match its content
+    CrcMismatch(ParseError),
+    /// boxcars couldn't decode the replay
+    Parse(ParseError),
+    /// A header property is missing or mistyped
+    Header(HeaderError),
+    /// Every parse worker and queue slot is taken
+    Busy {
+        retry_after: u64,
+    },
+    Unauthorized,
+    NotFound(String),
+    Internal(String),
+}
+
+impl ApiError {
+    pub fn status(&self) -> StatusCode {
+        match self {
+            ApiError::NotMultipart => StatusCode::UNSUPPORTED_MEDIA_TYPE,
+            ApiError::BadMultipart(_)
+            | ApiError::EmptyBody
+            | ApiError::WrongField { .. }
+            | ApiError::BadArchive(_)
+            | ApiError::InvalidCallback(_) => StatusCode::BAD_REQUEST,
+            ApiError::TooLarge { .. } | ApiError::TooManyFiles { .. } => {
+                StatusCode::PAYLOAD_TOO_LARGE
+            }
+            ApiError::CrcMismatch(_) | ApiError::Parse(_) | ApiError::Header(_) => {
+                StatusCode::UNPROCESSABLE_ENTITY
+            }
+            ApiError::Busy { .. } => StatusCode::SERVICE_UNAVAILABLE,
+            ApiError::Unauthorized => StatusCode::UNAUTHORIZED,
+            ApiError::NotFound(_) => StatusCode::NOT_FOUND,
+            ApiError::Internal(_) => StatusCode::INTERNAL_SERVER_ERROR,
+        }
+    }
+
+    /// Stable identifier clients can match on
+    pub fn code(&self) -> &'static str {
+        match self {
+            ApiError::NotMultipart => "not_multipart",
+            ApiError::BadMultipart(_) => "bad_multipart",
+            ApiError::EmptyBody => "empty_body",
+            ApiError::WrongField { .. } => "wrong_field",
+            ApiError::BadArchive(_) => "bad_archive",
+            ApiError::InvalidCallback(_) => "invalid_callback",
+            ApiError::TooLarge { .. } => "file_too_large",
+            ApiError::TooManyFiles { .. } => "too_many_files",
+            ApiError::CrcMismatch(_) => "crc_mismatch",
+            ApiError::Parse(_) => "parse_error",
+            ApiError::Header(_) => "invalid_header",
+            ApiError::Busy { .. } => "busy",
+            ApiError::Unauthorized => "unauthorized",
+            ApiError::NotFound(_) => "not_found",
+            ApiError::Internal(_) => "internal",
+        }
+    }
+
+    /// Every machine code, for the OpenAPI document
+    pub const CODES: [&'static str; 15] = [
+        "not_multipart",
+        "bad_multipart",
+        "empty_body",
+        "wrong_field",
+        "bad_archive",
+        "invalid_callback",
+        "file_too_large",
+        "too_many_files",
+        "crc_mismatch",
+        "parse_error",
+        "invalid_header",
+        "busy",
+        "unauthorized",
+        "not_found",
+        "internal",
+    ];
+}
+
+impl fmt::Display for ApiError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ApiError::NotMultipart => write!(f, "Expected a multipart/form-data upload"),
+            ApiError::BadMultipart(e) => write!(f, "Invalid multipart body: {}", e),
+            ApiError::EmptyBody => write!(f, "No replay file received"),
+            ApiError::WrongField { found } => write!(
+                f,
+                "Expected the replay in a field named \"file\", found {:?}",
+                found
+            ),
+            ApiError::BadArchive(e) => write!(f, "Invalid zip archive: {}", e),
+            ApiError::InvalidCallback(e) => write!(f, "Invalid callback URL {}", e),
+            ApiError::TooLarge { limit } => {
+                write!(f, "Upload is larger than {} MB", limit / 1024 / 1024)
+            }
+            ApiError::TooManyFiles { limit } => {
+                write!(f, "A batch can hold at most {} replays", limit)
+            }
+            ApiError::CrcMismatch(e) | ApiError::Parse(e) => write!(f, "{}", e),
+            ApiError::Header(e) => write!(f, "{}", e),
+            ApiError::Busy { retry_after } => write!(
+                f,
+                "Too many replays are being parsed, retry in {} seconds",
+                retry_after
+            ),
+            ApiError::Unauthorized => write!(f, "Invalid admin token"),
+            ApiError::NotFound(e) | ApiError::Internal(e) => write!(f, "{}", e),
+        }
+    }
+}
+
+impl std::error::Error for ApiError {}
+
+impl IntoResponse for ApiError {
+    fn into_response(self) -> Response {
+        let mut body = json!({ "error": self.to_string(), "code": self.code() });
+        if let ApiError::Header(e) = &self {
+            body["field"] = json!(e.field());
+        }
+        let mut response = (self.status(), Json(body)).into_response();
+        if let ApiError::Busy { retry_after } = self {
+            response
+                .headers_mut()
+                .insert(RETRY_AFTER, HeaderValue::from(retry_after));
+        }
+        response
+    }
+}
+
+impl From<ParseError> for ApiError {
+    fn from(e: ParseError) -> Self {
+        match e {
+            ParseError::CrcMismatch(..) | ParseError::CorruptReplay(..) => ApiError::CrcMismatch(e),
+            e => ApiError::Parse(e),
+        }
+    }
+}
+
+impl From<HeaderError> for ApiError {
+    fn from(e: HeaderError) -> Self {
+        ApiError::Header(e)
+    }
+}
+
+impl From<MultipartRejection> for ApiError {
+    fn from(_: MultipartRejection) -> Self {
+        ApiError::NotMultipart
+    }
+}
+
+impl From<MultipartError> for ApiError {
+    fn from(e: MultipartError) -> Self {
+        if e.status() == StatusCode::PAYLOAD_TOO_LARGE {
+            ApiError::TooLarge {
+                limit: crate::max_upload_bytes(),
+            }
+        } else {
+            ApiError::BadMultipart(e.body_text())
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::collections::HashSet;
+
+    #[test]
+    fn codes_lists_every_code() {
+        let errors = [
+            ApiError::NotMultipart,
+            ApiError::BadMultipart(String::new()),
+            ApiError::EmptyBody,
+            ApiError::WrongField { found: vec![] },
+            ApiError::BadArchive(String::new()),
+            ApiError::InvalidCallback(String::new()),
+            ApiError::TooLarge { limit: 0 },
+            ApiError::TooManyFiles { limit: 0 },
+            ApiError::CrcMismatch(ParseError::CrcMismatch(0, 1)),
+            ApiError::Parse(ParseError::ListTooLarge(0)),
+            ApiError::Header(HeaderError::Missing {
+                field: String::new(),
+            }),
+            ApiError::Busy { retry_after: 0 },
+            ApiError::Unauthorized,
+            ApiError::NotFound(String::new()),
+            ApiError::Internal(String::new()),
+        ];
+
+        let codes: HashSet<&str> = errors.iter().map(ApiError::code).collect();
+        assert_eq!(codes.len(), errors.len(), "codes must be unique");
+        assert_eq!(codes, HashSet::from(ApiError::CODES));
+    }
+}
@@ -0,0 +1,331 @@
+use crate::types::player::{
+    Handle, HandleFields, NpFields, NpId, Platform, PlatformValue, PlayerFields, PlayerId,
+};
+use crate::types::{Goal, Highlight, PlayerStat, Properties};
+
+use boxcars::HeaderProp;
+use std::fmt;
+
+/// A header property that is absent or doesn't have the type the schema expects
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum HeaderError {
+    Missing {
+        field: String,
+    },
+    WrongType {
+        field: String,
+        expected: &'static str,
+        found: &'static str,
+    },
+}
+
+impl HeaderError {
+    pub fn field(&self) -> &str {
+        match self {
+            HeaderError::Missing { field } | HeaderError::WrongType { field, .. } => field,
+        }
+    }
+}
+
+impl fmt::Display for HeaderError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            HeaderError::Missing { field } => write!(f, "missing header property {}", field),
+            HeaderError::WrongType {
+                field,
+                expected,
+                found,
+            } => write!(
+                f,
+                "header property {} should be {} but is {}",
+                field, expected, found
+            ),
+        }
+    }
+}
+
+impl std::error::Error for HeaderError {}
+
+/// Typed access to a list of header properties; `path` prefixes field names in errors
+/// (e.g. `PlayerStats[1].PlayerID.Uid`)
+struct Fields<'a> {
+    path: String,
+    props: &'a [(String, HeaderProp)],
+}
+
+impl<'a> Fields<'a> {
+    fn new(path: String, props: &'a [(String, HeaderProp)]) -> Self {
+        Self { path, props }
+    }
+
+    fn path(&self, key: &str) -> String {
+        if self.path.is_empty() {
+            key.to_string()
+        } else {
+            format!("{}.{}", self.path, key)
+        }
+    }
+
+    /// Read an optional property, failing if it is present with the wrong type
+    fn optional<T>(
+        &self,
+        key: &str,
+        expected: &'static str,
+        read: impl Fn(&'a HeaderProp) -> Option<T>,
+    ) -> Result<Option<T>, HeaderError> {
+        let Some((_, prop)) = self.props.iter().find(|(k, _)| k == key) else {
+            return Ok(None);
+        };
+        read(prop).map(Some).ok_or_else(|| HeaderError::WrongType {
+            field: self.path(key),
+            expected,
+            found: kind(prop),
+        })
+    }
+
+    fn required<T>(
+        &self,
+        key: &str,
+        expected: &'static str,
+        read: impl Fn(&'a HeaderProp) -> Option<T>,
+    ) -> Result<T, HeaderError> {
+        self.optional(key, expected, read)?
+            .ok_or_else(|| HeaderError::Missing {
+                field: self.path(key),
+            })
+    }
+
+    fn int(&self, key: &str) -> Result<i32, HeaderError> {
+        self.required(key, "Int", HeaderProp::as_i32)
+    }
+
+    fn int_opt(&self, key: &str) -> Result<Option<i32>, HeaderError> {
+        self.optional(key, "Int", HeaderProp::as_i32)
+    }
+
+    fn uint(&self, key: &str) -> Result<u32, HeaderError> {
+        self.required(key, "non-negative Int", as_u32)
+    }
+
+    fn uint_opt(&self, key: &str) -> Result<Option<u32>, HeaderError> {
+        self.optional(key, "non-negative Int", as_u32)
+    }
+
+    fn float(&self, key: &str) -> Result<f32, HeaderError> {
+        self.required(key, "Float", as_f32)
+    }
+
+    fn string(&self, key: &str) -> Result<String, HeaderError> {
+        self.required(key, "Str or Name", as_text)
+    }
+
+    fn string_opt(&self, key: &str) -> Result<Option<String>, HeaderError> {
+        self.optional(key, "Str or Name", as_text)
+    }
+
+    fn array(&self, key: &str) -> Result<&'a [Vec<(String, HeaderProp)>], HeaderError> {
+        Ok(self
+            .optional(key, "Array", |p| p.as_array().map(Vec::as_slice))?
+            .unwrap_or(&[]))
+    }
+
+    /// Name and fields of a `Struct` property
+    fn structure(&self, key: &str) -> Result<Option<(String, Fields<'a>)>, HeaderError> {
+        let path = self.path(key);
+        self.optional(key, "Struct", |p| match p {
+            HeaderProp::Struct { name, fields } => {
+                Some((name.clone(), Fields::new(path.clone(), fields)))
+            }
+            _ => None,
+        })
+    }
+
+    fn byte(&self, key: &str) -> Result<(String, String), HeaderError> {
+        self.required(key, "Byte", |p| match p {
+            HeaderProp::Byte { kind, value } => {
+                Some((kind.clone(), value.clone().unwrap_or_default()))
+            }
+            _ => None,
+        })
+    }
+
+    /// Typed elements of an array property
+    fn items<T>(
+        &self,
+        key: &str,
+        convert: impl Fn(Fields<'a>) -> Result<T, HeaderError>,
+    ) -> Result<Vec<T>, HeaderError> {
+        self.array(key)?
+            .iter()
+            .enumerate()
+            .map(|(i, props)| convert(Fields::new(format!("{}[{}]", self.path(key), i), props)))
+            .collect()
+    }
+}
+
+fn as_u8(prop: &HeaderProp) -> Option<u8> {
+    prop.as_i32().and_then(|i| u8::try_from(i).ok())
+}
+
+fn as_u32(prop: &HeaderProp) -> Option<u32> {
+    prop.as_i32().and_then(|i| u32::try_from(i).ok())
+}
+
+fn as_f32(prop: &HeaderProp) -> Option<f32> {
+    match prop {
+        HeaderProp::Float(f) => Some(*f),
+        HeaderProp::Int(i) => Some(*i as f32),
+        _ => None,
+    }
+}
+
+/// Text of a string-like property; 64-bit IDs are stored as `QWord`
+fn as_text(prop: &HeaderProp) -> Option<String> {
+    match prop {
+        HeaderProp::Str(s) | HeaderProp::Name(s) => Some(s.clone()),
+        HeaderProp::QWord(q) => Some(q.to_string()),
+        _ => None,
+    }
+}
+
+fn kind(prop: &HeaderProp) -> &'static str {
+    match prop {
+        HeaderProp::Array(_) => "Array",
+        HeaderProp::Bool(_) => "Bool",
+        HeaderProp::Byte { .. } => "Byte",
+        HeaderProp::Float(_) => "Float",
+        HeaderProp::Int(_) => "Int",
+        HeaderProp::Name(_) => "Name",
+        HeaderProp::QWord(_) => "QWord",
+        HeaderProp::Str(_) => "Str",
+        HeaderProp::Struct { .. } => "Struct",
+    }
+}
+
+impl TryFrom<&[(String, HeaderProp)]> for Properties {
+    type Error = HeaderError;
+
+    fn try_from(props: &[(String, HeaderProp)]) -> Result<Self, Self::Error> {
+        let f = Fields::new(String::new(), props);
+
+        // Scores are left out of the header while they are 0
+        let score = |key: &str| -> Result<u8, HeaderError> {
+            f.optional(key, "non-negative Int", as_u8)
+                .map(Option::unwrap_or_default)
+        };
+
+        Ok(Properties {
+            team_size: f.required("TeamSize", "non-negative Int", as_u8)?,
+            team0_score: score("Team0Score")?,
+            team1_score: score("Team1Score")?,
+            total_seconds_played: f.float("TotalSecondsPlayed")?,
+            match_start_epoch: f.string_opt("MatchStartEpoch")?,
+            goals: f.items("Goals", goal)?,
+            highlights: f.items("HighLights", highlight)?,
+            player_stats: f.items("PlayerStats", player_stat)?,
+            replay_name: f.string_opt("ReplayName")?,
+            replay_version: f.uint("ReplayVersion")?,
+            replay_last_save_version: f.uint_opt("ReplayLastSaveVersion")?,
+            game_version: f.uint_opt("GameVersion")?,
+            build_id: f.int_opt("BuildID")?,
+            changelist: f.uint_opt("Changelist")?,
+            build_version: f.string_opt("BuildVersion")?,
+            reserve_megabytes: f.uint_opt("ReserveMegabytes")?,
+            record_fps: f.float("RecordFPS")?,
+            keyframe_delay: f.float("KeyframeDelay")?,
+            max_channels: f.uint("MaxChannels")?,
+            max_replay_size_mb: f.uint_opt("MaxReplaySizeMB")?,
+            id: f.string("Id")?,
+            match_guid: f.string_opt("MatchGuid")?,
+            map_name: f.string("MapName")?,
+            date: f.string("Date")?,
+            num_frames: f.uint("NumFrames")?,
+            match_type: f.string("MatchType")?,
+        })
+    }
+}
+
+impl TryFrom<&[(String, HeaderProp)]> for PlayerStat {
+    type Error = HeaderError;
+
+    fn try_from(props: &[(String, HeaderProp)]) -> Result<Self, Self::Error> {
+        player_stat(Fields::new(String::new(), props))
+    }
+}
+
+fn goal(f: Fields) -> Result<Goal, HeaderError> {
+    Ok(Goal {
+        frame: f.uint("frame")?,
+        player_name: f.string("PlayerName")?,
+        player_team: f.required("PlayerTeam", "team number (0 or 1)", |p| {
+            as_u8(p).filter(|t| *t <= 1)
+        })?,
+    })
+}
+
+fn highlight(f: Fields) -> Result<Highlight, HeaderError> {
+    Ok(Highlight {
+        frame: f.uint("frame")?,
+        car_name: f.string("CarName")?,
+        ball_name: f.string("BallName")?,
+        goal_actor_name: f.string_opt("GoalActorName")?,
+    })
+}
+
+fn player_stat(f: Fields) -> Result<PlayerStat, HeaderError> {
+    let (kind, value) = f.byte("Platform")?;
+
+    Ok(PlayerStat {
+        player_id: f
+            .structure("PlayerID")?
+            .map(|(name, fields)| player_id(name, fields))
+            .transpose()?,
+        name: f.string("Name")?,
+        platform: PlatformValue { kind, value },
+        online_id: f.string("OnlineID")?,
+        team: f.required("Team", "non-negative Int", as_u8)?,
+        score: f.int("Score")?,
+        goals: f.int("Goals")?,
+        assists: f.int("Assists")?,
+        saves: f.int("Saves")?,
+        shots: f.int("Shots")?,
+        b_bot: f.required("bBot", "Bool", HeaderProp::as_bool)?,
+    })
+}
+
+fn player_id(name: String, f: Fields) -> Result<PlayerId, HeaderError> {
+    let (kind, value) = f.byte("Platform")?;
+
+    Ok(PlayerId {
+        name,
+        fields: PlayerFields {
+            uid: f.string("Uid")?,
+            np_id: f
+                .structure("NpId")?
+                .map(|(name, fields)| np_id(name, fields))
+                .transpose()?,
+            epic_account_id: f.string_opt("EpicAccountId")?,
+            platform: Platform { kind, value },
+        },
+    })
+}
+
+fn np_id(name: String, f: Fields) -> Result<NpId, HeaderError> {
+    let (handle_name, handle) = f.structure("Handle")?.ok_or_else(|| HeaderError::Missing {
+        field: f.path("Handle"),
+    })?;
+
+    Ok(NpId {
+        name,
+        fields: NpFields {
+            handle: Handle {
+                name: handle_name,
+                fields: HandleFields {
+                    data: handle.string("Data")?,
+                },
+            },
+            opt: f.string("Opt")?,
+            reserved: f.string("Reserved")?,
+        },
+    })
+}
@@ -1,3 +1,4 @@
+use crate::types::Goal;
 use boxcars::HeaderProp;
 
 /// Returns `true` if the game went into overtime:
@@ -22,6 +23,15 @@ pub fn get_overtime_seconds(duration: f64, overtime: bool) -> u32 {
     }
 }
 
+/// Goals as a rounded percentage of shots, 0 when there were no shots
+pub fn shooting_percentage(goals: u32, shots: u32) -> u32 {
+    if shots > 0 {
+        (goals as f32 / shots as f32 * 100.0).round() as u32
+    } else {
+        0
+    }
+}
+
 /// Helper: safely get f32 from props
 pub fn get_f32(props: &[(String, HeaderProp)], key: &str) -> f32 {
     props
@@ -43,3 +53,27 @@ pub fn get_i32(props: &[(String, HeaderProp)], key: &str) -> i32 {
         .and_then(|(_, v)| v.as_i32())
         .unwrap_or(0)
 }
+
+/// Helper: goals listed in the header `Goals` array, in the order they were scored
+pub fn get_goals(props: &[(String, HeaderProp)]) -> Vec<Goal> {
+    let goals = props
+        .iter()
+        .find(|(k, _)| k == "Goals")
+        .and_then(|(_, v)| v.as_array())
+        .map(Vec::as_slice)
+        .unwrap_or(&[]);
+
+    goals
+        .iter()
+        .filter_map(|goal| {
+            let get = |key: &str| goal.iter().find(|(k, _)| k == key).map(|(_, v)| v);
+            Some(Goal {
+                frame: u32::try_from(get("frame")?.as_i32()?).ok()?,
+                player_name: get("PlayerName")?.as_string()?.to_string(),
+                player_team: u8::try_from(get("PlayerTeam")?.as_i32()?)
+                    .ok()
+                    .filter(|t| *t <= 1)?,
+            })
+        })
+        .collect()
+}
@@ -0,0 +1,584 @@
+use crate::cache::{Lookup, ParseCache};
+use crate::catalog::CatalogStore;
+use crate::error::ApiError;
+use crate::matches::MatchRegistry;
+use crate::pool::ParsePool;
+use crate::types::BallchasingReplay;
+
+use axum::{body::Bytes, extract::Multipart};
+use chrono::{DateTime, Utc};
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::net::{IpAddr, SocketAddr};
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, Mutex, MutexGuard};
+use std::time::Duration;
+use tokio::sync::{mpsc, Semaphore};
+use ts_rs::TS;
+use uuid::Uuid;
+
+/// Where jobs are kept when `JOBS_DIR` is not set
+const DEFAULT_JOBS_DIR: &str = "data/jobs";
+
+/// Deliveries of a callback before it is marked as failed
+const CALLBACK_ATTEMPTS: u32 = 3;
+
+const CALLBACK_TIMEOUT: Duration = Duration::from_secs(10);
+
+/// Jobs waiting for a worker when `MAX_QUEUED_JOBS` is not set
+const DEFAULT_MAX_QUEUED_JOBS: usize = 100;
+
+/// Hours finished jobs are kept when `JOB_TTL_HOURS` is not set
+const DEFAULT_JOB_TTL_HOURS: i64 = 24;
+
+/// How often finished jobs are checked for expiry
+const EXPIRE_INTERVAL: Duration = Duration::from_secs(10 * 60);
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, TS, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum JobStatus {
+    Queued,
+    Running,
+    Done,
+    Failed,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, TS, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum CallbackStatus {
+    Pending,
+    Delivered,
+    Failed,
+}
+
+/// Why a job failed, with the machine code `/parse` would have answered with
+#[derive(Debug, Clone, Serialize, Deserialize, TS, JsonSchema)]
+pub struct JobError {
+    pub error: String,
+    pub code: String,
+}
+
+/// A replay queued for parsing; stored as `{id}.json` next to the uploaded
+/// `{id}.replay` and, once done, the `{id}.result.json` parse result
+#[derive(Debug, Clone, Serialize, Deserialize, TS, JsonSchema)]
+pub struct Job {
+    pub id: String,
+    pub status: JobStatus,
+    pub file_name: Option<String>,
+    pub callback_url: Option<String>,
+    /// `None` without a callback URL
+    pub callback_status: Option<CallbackStatus>,
+    pub created_at: String,
+    pub updated_at: String,
+    pub error: Option<JobError>,
+}
+
+/// A job with its parse result, as returned by `GET /jobs/{id}` and sent to callbacks
+#[derive(Debug, Serialize, TS, JsonSchema)]
+pub struct JobReport {
+    #[serde(flatten)]
+    pub job: Job,
+    pub result: Option<Box<BallchasingReplay>>,
+}
+
+/// Jobs by ID, backed by a directory so queued jobs survive a restart; finished jobs
+/// are removed once they are older than `JOB_TTL_HOURS`
+pub struct JobQueue {
+    dir: PathBuf,
+    jobs: Mutex<HashMap<String, Job>>,
+    queue: mpsc::UnboundedSender<String>,
+    max_queued: usize,
+    ttl: chrono::Duration,
+    catalog: Arc<CatalogStore>,
+    parser: Arc<ParsePool>,
+    cache: Arc<ParseCache>,
+    matches: Arc<MatchRegistry>,
+}
+
+impl JobQueue {
+    /// Open `JOBS_DIR`, re-queue the jobs a previous run didn't finish, and start
+    /// working through the queue; at most `MAX_QUEUED_JOBS` wait at once
+    pub fn open(
+        catalog: Arc<CatalogStore>,
+        parser: Arc<ParsePool>,
+        cache: Arc<ParseCache>,
+        matches: Arc<MatchRegistry>,
+    ) -> Result<Arc<Self>, String> {
+        let dir = PathBuf::from(
+            std::env::var("JOBS_DIR").unwrap_or_else(|_| DEFAULT_JOBS_DIR.to_string()),
+        );
+        std::fs::create_dir_all(&dir)
+            .map_err(|e| format!("failed to create {}: {}", dir.display(), e))?;
+
+        let mut jobs = load_jobs(&dir)?;
+        let mut unfinished: Vec<&mut Job> = jobs
+            .values_mut()
+            .filter(|job| matches!(job.status, JobStatus::Queued | JobStatus::Running))
+            .collect();
+        unfinished.sort_by(|a, b| a.created_at.cmp(&b.created_at));
+
+        let (queue, receiver) = mpsc::unbounded_channel();
+        for job in unfinished {
+            job.status = JobStatus::Queued;
+            let _ = queue.send(job.id.clone());
+        }
+        let undelivered: Vec<String> = jobs
+            .values()
+            .filter(|job| {
+                matches!(job.status, JobStatus::Done | JobStatus::Failed)
+                    && job.callback_status == Some(CallbackStatus::Pending)
+            })
+            .map(|job| job.id.clone())
+            .collect();
+        println!(
+            "🗂️ {} jobs in {}, {} re-queued",
+            jobs.len(),
+            dir.display(),
+            queue_len(&jobs)
+        );
+
+        let max_queued = std::env::var("MAX_QUEUED_JOBS")
+            .ok()
+            .and_then(|n| n.parse().ok())
+            .unwrap_or(DEFAULT_MAX_QUEUED_JOBS);
+        let ttl_hours = std::env::var("JOB_TTL_HOURS")
+            .ok()
+            .and_then(|n| n.parse().ok())
+            .unwrap_or(DEFAULT_JOB_TTL_HOURS);
+        let store = Arc::new(Self {
+            dir,
+            jobs: Mutex::new(jobs),
+            queue,
+            max_queued,
+            ttl: chrono::Duration::hours(ttl_hours),
+            catalog,
+            parser,
+            cache,
+            matches,
+        });
+
+        tokio::spawn(store.clone().work(receiver));
+        tokio::spawn(store.clone().expire());
+        for id in undelivered {
+            tokio::spawn(store.clone().deliver(id));
+        }
+        Ok(store)
+    }
+
+    /// Store the replay and queue it; fails with `ApiError::Busy` when `MAX_QUEUED_JOBS`
+    /// jobs are already waiting
+    pub async fn submit(
+        &self,
+        data: Bytes,
+        file_name: Option<String>,
+        callback_url: Option<String>,
+    ) -> Result<Job, ApiError> {
+        let now = Utc::now().to_rfc3339();
+        let job = Job {
+            id: Uuid::new_v4().to_string(),
+            status: JobStatus::Queued,
+            file_name,
+            callback_status: callback_url.as_ref().map(|_| CallbackStatus::Pending),
+            callback_url,
+            created_at: now.clone(),
+            updated_at: now,
+            error: None,
+        };
+
+        {
+            let mut jobs = self.jobs();
+            if queue_len(&jobs) >= self.max_queued {
+                return Err(ApiError::Busy {
+                    retry_after: self.parser.retry_after(),
+                });
+            }
+            // Takes the queue slot now; the worker only hears of it once it is stored
+            jobs.insert(job.id.clone(), job.clone());
+        }
+
+        let stored = match tokio::fs::write(self.replay_path(&job.id), &data).await {
+            Ok(()) => self.save(&job).await,
+            Err(e) => Err(internal(e)),
+        };
+        if let Err(e) = stored {
+            self.jobs().remove(&job.id);
+            let _ = tokio::fs::remove_file(self.replay_path(&job.id)).await;
+            return Err(e);
+        }
+        self.queue
+            .send(job.id.clone())
+            .map_err(|_| ApiError::Internal("Job queue is closed".into()))?;
+        Ok(job)
+    }
+
+    /// The job and, once it is done, its parse result
+    pub async fn report(&self, id: &str) -> Result<JobReport, ApiError> {
+        let job = self
+            .jobs()
+            .get(id)
+            .cloned()
+            .ok_or_else(|| ApiError::NotFound(format!("No job {}", id)))?;
+
+        let result = if job.status == JobStatus::Done {
+            let content = tokio::fs::read(self.result_path(id))
+                .await
+                .map_err(internal)?;
+            Some(serde_json::from_slice(&content).map_err(internal)?)
+        } else {
+            None
+        };
+        Ok(JobReport { job, result })
+    }
+
+    /// Run queued jobs, as many at once as the parse pool has workers
+    async fn work(self: Arc<Self>, mut receiver: mpsc::UnboundedReceiver<String>) {
+        let slots = Arc::new(Semaphore::new(self.parser.workers()));
+
+        while let Some(id) = receiver.recv().await {
+            let Ok(slot) = slots.clone().acquire_owned().await else {
+                return;
+            };
+            let store = self.clone();
+            tokio::spawn(async move {
+                store.run(&id).await;
+                drop(slot);
+                store.deliver(id).await;
+            });
+        }
+    }
+
+    async fn run(&self, id: &str) {
+        self.update(id, |job| job.status = JobStatus::Running).await;
+
+        let outcome = self.parse(id).await;
+        self.update(id, |job| match outcome {
+            Ok(()) => job.status = JobStatus::Done,
+            Err(e) => {
+                job.status = JobStatus::Failed;
+                job.error = Some(JobError {
+                    error: e.to_string(),
+                    code: e.code().to_string(),
+                });
+            }
+        })
+        .await;
+
+        // Only once the outcome is saved, so a crash before it re-runs the job
+        let _ = tokio::fs::remove_file(self.replay_path(id)).await;
+    }
+
+    async fn parse(&self, id: &str) -> Result<(), ApiError> {
+        let data = tokio::fs::read(self.replay_path(id))
+            .await
+            .map_err(internal)?;
+
+        let mut replay = match self.cache.lookup(data.into(), self.catalog.get()).await? {
+            Lookup::Hit(replay) => *replay,
+            Lookup::Miss(parse) => self.parser.run_queued(parse).await?,
+        };
+        self.catalog.record_unknown(&replay);
+        self.matches.register(&mut replay);
+
+        let content = serde_json::to_vec(&replay).map_err(internal)?;
+        write_atomic(&self.result_path(id), &content)
+            .await
+            .map_err(internal)
+    }
+
+    /// POST the finished job to its callback URL, retrying with backoff; the host is
+    /// resolved and checked again so it can't have been pointed at a private address since
+    async fn deliver(self: Arc<Self>, id: String) {
+        let Ok(report) = self.report(&id).await else {
+            return;
+        };
+        let Some(url) = report.job.callback_url.clone() else {
+            return;
+        };
+
+        let client = match callback_client(&url).await {
+            Ok(client) => Some(client),
+            Err(e) => {
+                eprintln!("⚠️ Callback for job {} refused: {}", id, e);
+                None
+            }
+        };
+
+        let mut delivered = false;
+        for attempt in 0..CALLBACK_ATTEMPTS {
+            let Some(client) = &client else {
+                break;
+            };
+            if attempt > 0 {
+                tokio::time::sleep(Duration::from_secs(2u64.pow(attempt))).await;
+            }
+            match client.post(&url).json(&report).send().await {
+                Ok(response) if response.status().is_success() => {
+                    delivered = true;
+                    break;
+                }
+                Ok(response) => {
+                    eprintln!("⚠️ Callback for job {} got {}", id, response.status())
+                }
+                Err(e) => eprintln!("⚠️ Callback for job {} failed: {}", id, e),
+            }
+        }
+
+        self.update(&id, |job| {
+            job.callback_status = Some(if delivered {
+                CallbackStatus::Delivered
+            } else {
+                CallbackStatus::Failed
+            });
+        })
+        .await;
+    }
+
+    /// Remove finished jobs older than the TTL, every `EXPIRE_INTERVAL`
+    async fn expire(self: Arc<Self>) {
+        let mut interval = tokio::time::interval(EXPIRE_INTERVAL);
+        loop {
+            interval.tick().await;
+            let cutoff = Utc::now() - self.ttl;
+            let expired: Vec<String> = {
+                let mut jobs = self.jobs();
+                let ids: Vec<String> = jobs
+                    .values()
+                    .filter(|job| is_expired(job, cutoff))
+                    .map(|job| job.id.clone())
+                    .collect();
+                for id in &ids {
+                    jobs.remove(id);
+                }
+                ids
+            };
+
+            for id in &expired {
+                for path in [
+                    self.dir.join(format!("{}.json", id)),
+                    self.result_path(id),
+                    self.replay_path(id),
+                ] {
+                    let _ = tokio::fs::remove_file(path).await;
+                }
+            }
+            if !expired.is_empty() {
+                println!("🧹 Removed {} expired jobs", expired.len());
+            }
+        }
+    }
+
+    /// Change a job and write it back to disk
+    async fn update(&self, id: &str, change: impl FnOnce(&mut Job)) {
+        let job = {
+            let mut jobs = self.jobs();
+            let Some(job) = jobs.get_mut(id) else {
+                return;
+            };
+            change(job);
+            job.updated_at = Utc::now().to_rfc3339();
+            job.clone()
+        };
+        if let Err(e) = self.save(&job).await {
+            eprintln!("⚠️ Failed to save job {}: {}", id, e);
+        }
+    }
+
+    async fn save(&self, job: &Job) -> Result<(), ApiError> {
+        let content = serde_json::to_vec_pretty(job).map_err(internal)?;
+        write_atomic(&self.dir.join(format!("{}.json", job.id)), &content)
+            .await
+            .map_err(internal)
+    }
+
+    fn replay_path(&self, id: &str) -> PathBuf {
+        self.dir.join(format!("{}.replay", id))
+    }
+
+    fn result_path(&self, id: &str) -> PathBuf {
+        self.dir.join(format!("{}.result.json", id))
+    }
+
+    fn jobs(&self) -> MutexGuard<'_, HashMap<String, Job>> {
+        match self.jobs.lock() {
+            Ok(jobs) => jobs,
+            Err(poisoned) => poisoned.into_inner(),
+        }
+    }
+}
+
+/// Replay and optional `callback_url` of a `POST /jobs` form
+pub async fn read_job_upload(
+    mut multipart: Multipart,
+) -> Result<(Bytes, Option<String>, Option<String>), ApiError> {
+    let mut replay = None;
+    let mut callback_url = None;
+
+    while let Some(field) = multipart.next_field().await? {
+        match field.name() {
+            Some("file") => {
+                let file_name = field.file_name().map(str::to_string);
+                replay = Some((field.bytes().await?, file_name));
+            }
+            Some("callback_url") => {
+                let url = field.text().await?;
+                callback_url = Some(check_callback_url(url.trim()).await?);
+            }
+            _ => {}
+        }
+    }
+
+    match replay {
+        Some((data, file_name)) if !data.is_empty() => Ok((data, file_name, callback_url)),
+        _ => Err(ApiError::EmptyBody),
+    }
+}
+
+/// An http(s) URL whose host is allowed by `callback_address`
+async fn check_callback_url(url: &str) -> Result<String, ApiError> {
+    let invalid =
+        |reason: &dyn std::fmt::Display| ApiError::InvalidCallback(format!("{}: {}", url, reason));
+    let parsed = reqwest::Url::parse(url).map_err(|e| invalid(&e))?;
+    if !matches!(parsed.scheme(), "http" | "https") {
+        return Err(invalid(&"only http and https are supported"));
+    }
+    callback_address(&parsed).await.map_err(|e| invalid(&e))?;
+    Ok(parsed.to_string())
+}
+
+/// Client that sends to the checked address of `url` only, without following redirects
+async fn callback_client(url: &str) -> Result<reqwest::Client, String> {
+    let parsed = reqwest::Url::parse(url).map_err(|e| e.to_string())?;
+    let address = callback_address(&parsed).await?;
+    let host = parsed.host_str().unwrap_or_default();
+
+    reqwest::Client::builder()
+        .timeout(CALLBACK_TIMEOUT)
+        .redirect(reqwest::redirect::Policy::none())
+        .resolve(host, address)
+        .build()
+        .map_err(|e| e.to_string())
+}
+
+/// Address to deliver a callback to. With `CALLBACK_HOSTS` (comma-separated host names)
+/// only those hosts are allowed, wherever they point; otherwise every address the host
+/// resolves to must be public, so callbacks can't reach loopback, link-local or private
+/// networks
+async fn callback_address(url: &reqwest::Url) -> Result<SocketAddr, String> {
+    let host = url.host_str().ok_or("no host")?;
+    let port = url.port_or_known_default().ok_or("no port")?;
+    // IPv6 literals come bracketed
+    let host = host.trim_start_matches('[').trim_end_matches(']');
+
+    let allowed_hosts = std::env::var("CALLBACK_HOSTS").ok();
+    if let Some(hosts) = &allowed_hosts {
+        let allowed = hosts
+            .split(',')
+            .map(str::trim)
+            .any(|allowed| allowed.eq_ignore_ascii_case(host));
+        if !allowed {
+            return Err(format!("{} is not in CALLBACK_HOSTS", host));
+        }
+    }
+
+    let addresses: Vec<SocketAddr> = tokio::net::lookup_host((host, port))
+        .await
+        .map_err(|e| format!("can't resolve {}: {}", host, e))?
+        .collect();
+    if allowed_hosts.is_none() {
+        if let Some(address) = addresses.iter().find(|a| !is_public(a.ip())) {
+            return Err(format!("{} resolves to non-public {}", host, address.ip()));
+        }
+    }
+    addresses
+        .into_iter()
+        .next()
+        .ok_or_else(|| format!("{} has no addresses", host))
+}
+
+/// Whether an address is routable on the internet
+fn is_public(ip: IpAddr) -> bool {
+    match ip {
+        IpAddr::V4(ip) => {
+            let [a, b, ..] = ip.octets();
+            !(ip.is_unspecified()
+                || ip.is_loopback()
+                || ip.is_private()
+                || ip.is_link_local()
+                || ip.is_broadcast()
+                || ip.is_documentation()
+                || ip.is_multicast()
+                // Carrier-grade NAT, 100.64.0.0/10
+                || (a == 100 && (64..128).contains(&b))
+                // "This network" and reserved, 0.0.0.0/8 and 240.0.0.0/4
+                || a == 0
+                || a >= 240)
+        }
+        IpAddr::V6(ip) => {
+            if let Some(v4) = ip.to_ipv4_mapped() {
+                return is_public(IpAddr::V4(v4));
+            }
+            let first = ip.segments()[0];
+            !(ip.is_unspecified()
+                || ip.is_loopback()
+                || ip.is_multicast()
+                // Unique local, fc00::/7
+                || (first & 0xfe00) == 0xfc00
+                // Link-local, fe80::/10
+                || (first & 0xffc0) == 0xfe80)
+        }
+    }
+}
+
+fn load_jobs(dir: &Path) -> Result<HashMap<String, Job>, String> {
+    let entries =
+        std::fs::read_dir(dir).map_err(|e| format!("failed to read {}: {}", dir.display(), e))?;
+    let mut jobs = HashMap::new();
+
+    for entry in entries.flatten() {
+        let path = entry.path();
+        let name = path
+            .file_name()
+            .and_then(|n| n.to_str())
+            .unwrap_or_default();
+        if !name.ends_with(".json") || name.ends_with(".result.json") {
+            continue;
+        }
+
+        let job = std::fs::read(&path)
+            .map_err(|e| e.to_string())
+            .and_then(|content| serde_json::from_slice::<Job>(&content).map_err(|e| e.to_string()));
+        match job {
+            Ok(job) => {
+                jobs.insert(job.id.clone(), job);
+            }
+            Err(e) => eprintln!("⚠️ Skipping job {}: {}", path.display(), e),
+        }
+    }
+
+    Ok(jobs)
+}
+
+/// Finished, with its callback settled, and last changed before `cutoff`
+fn is_expired(job: &Job, cutoff: DateTime<Utc>) -> bool {
+    matches!(job.status, JobStatus::Done | JobStatus::Failed)
+        && job.callback_status != Some(CallbackStatus::Pending)
+        && DateTime::parse_from_rfc3339(&job.updated_at).is_ok_and(|updated| updated < cutoff)
+}
+
+fn queue_len(jobs: &HashMap<String, Job>) -> usize {
+    jobs.values()
+        .filter(|job| job.status == JobStatus::Queued)
+        .count()
+}
+
+/// Write through a temporary file so a crash never leaves a half-written job behind
+async fn write_atomic(path: &Path, content: &[u8]) -> std::io::Result<()> {
+    let tmp = path.with_extension("tmp");
+    tokio::fs::write(&tmp, content).await?;
+    tokio::fs::rename(&tmp, path).await
+}
+
+fn internal(e: impl std::fmt::Display) -> ApiError {
+    ApiError::Internal(e.to_string())
+}
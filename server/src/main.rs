@@ -1,93 +1,422 @@
-use axum::{extract::Multipart, http::StatusCode, routing::post, Json, Router};
+use axum::{
+    body::Bytes,
+    extract::{multipart::MultipartRejection, DefaultBodyLimit, Multipart, Path, State},
+    http::{
+        header::{CONTENT_TYPE, LOCATION},
+        HeaderMap, HeaderName, StatusCode,
+    },
+    routing::{get, post},
+    Json, Router,
+};
 use boxcars::{NetworkParse, ParserBuilder, Replay};
 use serde_json::{json, Value};
+use std::collections::BTreeMap;
 use std::net::SocketAddr;
+use std::sync::Arc;
 use tokio::net::TcpListener;
 
+mod batch;
+mod cache;
+mod catalog;
+mod dates;
+mod error;
+mod header;
 mod helpers;
+mod jobs;
+mod matches;
 mod parser;
+mod pool;
+mod schema;
+mod stats;
 mod types;
 
-use crate::parser::parse_to_ballchasing;
-use crate::types::BallchasingReplay;
+use crate::batch::{read_batch_upload, BatchLimits, BatchResult};
+use crate::cache::{CacheStats, Lookup, ParseCache};
+use crate::catalog::{CatalogStore, UnknownProduct};
+use crate::error::ApiError;
+use crate::jobs::{read_job_upload, Job, JobQueue, JobReport};
+use crate::matches::MatchRegistry;
+use crate::parser::camera_summary;
+use crate::pool::ParsePool;
+use crate::types::{BallchasingReplay, PlayerCamera, Properties, Root};
+
+/// Upload limit used when `MAX_UPLOAD_MB` is not set
+const DEFAULT_MAX_UPLOAD_MB: usize = 10;
+
+/// Form field the replay is uploaded in
+const UPLOAD_FIELD: &str = "file";
+
+/// Largest accepted request body, from `MAX_UPLOAD_MB`
+pub fn max_upload_bytes() -> usize {
+    std::env::var("MAX_UPLOAD_MB")
+        .ok()
+        .and_then(|mb| mb.parse::<usize>().ok())
+        .unwrap_or(DEFAULT_MAX_UPLOAD_MB)
+        * 1024
+        * 1024
+}
+
+/// State shared by every handler
+#[derive(Clone)]
+struct AppState {
+    catalog: Arc<CatalogStore>,
+    parser: Arc<ParsePool>,
+    cache: Arc<ParseCache>,
+    matches: Arc<MatchRegistry>,
+    jobs: Arc<JobQueue>,
+}
 
 #[tokio::main]
 async fn main() {
+    let catalog = Arc::new(CatalogStore::from_env());
+    let parser = Arc::new(ParsePool::from_env());
+    let cache = Arc::new(ParseCache::from_env());
+    let matches = Arc::new(MatchRegistry::from_env());
+    if let Some(tz) = dates::source_timezone() {
+        println!("🕒 Reading replay dates as {}", tz);
+    }
+    let jobs = match JobQueue::open(
+        catalog.clone(),
+        parser.clone(),
+        cache.clone(),
+        matches.clone(),
+    ) {
+        Ok(jobs) => jobs,
+        Err(e) => {
+            eprintln!("❌ Failed to open job queue: {}", e);
+            std::process::exit(1);
+        }
+    };
+    let state = AppState {
+        catalog,
+        parser,
+        cache,
+        matches,
+        jobs,
+    };
+
     let app: Router = Router::new()
         .route("/parse", post(handle_parse))
-        .route("/output", post(|m| handle_output(m, NetworkParse::Always)))
+        .route(
+            "/parse/batch",
+            post(handle_parse_batch).layer(DefaultBodyLimit::max(
+                BatchLimits::from_env(max_upload_bytes()).max_bytes,
+            )),
+        )
+        .route("/jobs", post(handle_job_submit))
+        .route("/jobs/:id", get(handle_job_status))
+        .route("/camera", post(handle_camera))
+        .route("/header", post(handle_header))
+        .route(
+            "/output",
+            post(|State(s), m| handle_output(s, m, NetworkParse::Always)),
+        )
         .route(
             "/output/basic",
-            post(|m| handle_output(m, NetworkParse::Never)),
-        );
+            post(|State(s), m| handle_output(s, m, NetworkParse::Never)),
+        )
+        .route("/v1/output", post(handle_v1_output))
+        .route("/schema.ts", get(handle_schema_ts))
+        .route("/openapi.json", get(|| async { Json(schema::openapi()) }))
+        .route("/schemas/:name", get(handle_json_schema))
+        .route("/admin/catalog/reload", post(handle_catalog_reload))
+        .route("/admin/catalog/unknown", get(handle_catalog_unknown))
+        .route("/admin/cache", get(handle_cache_stats))
+        .route("/admin/cache/purge", post(handle_cache_purge))
+        .layer(DefaultBodyLimit::max(max_upload_bytes()))
+        .with_state(state);
 
     let port = std::env::var("PORT").unwrap_or_else(|_| "3030".to_string());
-    let addr = SocketAddr::from(([0, 0, 0, 0], port.parse().unwrap()));
+    let Ok(port) = port.parse::<u16>() else {
+        eprintln!("❌ Invalid PORT {:?}", port);
+        std::process::exit(1);
+    };
+    let addr = SocketAddr::from(([0, 0, 0, 0], port));
+
+    let listener = match TcpListener::bind(addr).await {
+        Ok(listener) => listener,
+        Err(e) => {
+            eprintln!("❌ Failed to bind {}: {}", addr, e);
+            std::process::exit(1);
+        }
+    };
+    if std::env::var("ADMIN_TOKEN").map_or(true, |token| token.is_empty()) {
+        println!("🔒 Admin routes disabled, set ADMIN_TOKEN to enable them");
+    }
     println!("🚀 Listening on http://{}", addr);
 
-    let listener = TcpListener::bind(addr).await.unwrap();
-    axum::serve(listener, app).await.unwrap();
+    if let Err(e) = axum::serve(listener, app).await {
+        eprintln!("❌ Server error: {}", e);
+        std::process::exit(1);
+    }
 }
 
 // /parse -> Returns Ballchasing-style response
 async fn handle_parse(
-    multipart: Multipart,
-) -> Result<Json<BallchasingReplay>, (StatusCode, Json<Value>)> {
-    parse_multipart_replay(multipart, |replay| {
-        let data = parse_to_ballchasing(replay);
-        Ok(Json(data))
+    State(state): State<AppState>,
+    multipart: Result<Multipart, MultipartRejection>,
+) -> Result<Json<BallchasingReplay>, ApiError> {
+    let data = read_replay_upload(multipart?).await?;
+    Ok(Json(parse_ballchasing(&state, data).await?))
+}
+
+// /parse/batch -> Parses every replay of the form, or of a zip archive, keyed by file name
+async fn handle_parse_batch(
+    State(state): State<AppState>,
+    multipart: Result<Multipart, MultipartRejection>,
+) -> Result<Json<BTreeMap<String, BatchResult>>, ApiError> {
+    let mut limits = BatchLimits::from_env(max_upload_bytes());
+    // Bigger batches could never be admitted to the parse pool
+    limits.max_files = limits.max_files.min(state.parser.capacity());
+    let files = read_batch_upload(multipart?, limits).await?;
+    let catalog = state.catalog.get();
+
+    // Cached replays are answered right away, the rest is parsed on the pool
+    let mut cached = Vec::new();
+    let mut names = Vec::new();
+    let mut parses = Vec::new();
+    for (name, data) in files {
+        let lookup = match data {
+            Ok(data) => state.cache.lookup(data, catalog.clone()).await,
+            Err(e) => Err(e),
+        };
+        match lookup {
+            Ok(Lookup::Hit(replay)) => cached.push((name, Ok(*replay))),
+            Ok(Lookup::Miss(parse)) => {
+                names.push(name);
+                parses.push(parse);
+            }
+            Err(e) => cached.push((name, Err(e))),
+        }
+    }
+    let parsed = state.parser.run_batch(parses).await?;
+
+    let mut results: Vec<_> = cached
+        .into_iter()
+        .chain(names.into_iter().zip(parsed))
+        .collect();
+    for replay in results.iter_mut().filter_map(|(_, r)| r.as_mut().ok()) {
+        state.catalog.record_unknown(replay);
+        state.matches.register(replay);
+    }
+    // Again, so replays of one match within the batch all list each other
+    for replay in results.iter_mut().filter_map(|(_, r)| r.as_mut().ok()) {
+        state.matches.register(replay);
+    }
+
+    let mut batch = BTreeMap::new();
+    for (name, result) in results {
+        batch.insert(name, BatchResult::from(result));
+    }
+    Ok(Json(batch))
+}
+
+// /jobs -> Queues a replay for parsing; the result is polled at /jobs/{id} or sent to
+// the optional callback_url
+async fn handle_job_submit(
+    State(state): State<AppState>,
+    multipart: Result<Multipart, MultipartRejection>,
+) -> Result<(StatusCode, [(HeaderName, String); 1], Json<Job>), ApiError> {
+    let (data, file_name, callback_url) = read_job_upload(multipart?).await?;
+    let job = state.jobs.submit(data, file_name, callback_url).await?;
+
+    Ok((
+        StatusCode::ACCEPTED,
+        [(LOCATION, format!("/jobs/{}", job.id))],
+        Json(job),
+    ))
+}
+
+// /jobs/{id} -> Job status, with the Ballchasing-style result once done
+async fn handle_job_status(
+    State(state): State<AppState>,
+    Path(id): Path<String>,
+) -> Result<Json<JobReport>, ApiError> {
+    state.jobs.report(&id).await.map(Json)
+}
+
+// /camera -> Returns each player's camera settings and ball cam usage
+async fn handle_camera(
+    State(state): State<AppState>,
+    multipart: Result<Multipart, MultipartRejection>,
+) -> Result<Json<Vec<PlayerCamera>>, ApiError> {
+    let data = read_replay_upload(multipart?).await?;
+    let replay = parse_ballchasing(&state, data).await?;
+    Ok(Json(camera_summary(&replay)))
+}
+
+// /v1/output -> Returns the full replay in our own versioned schema
+async fn handle_v1_output(
+    State(state): State<AppState>,
+    multipart: Result<Multipart, MultipartRejection>,
+) -> Result<Json<Root>, ApiError> {
+    parse_multipart_replay(&state, multipart?, NetworkParse::Always, |replay| {
+        Ok(Json(Root::from(&replay)))
     })
     .await
 }
 
+// /schema.ts -> TypeScript declarations of the /parse response types
+async fn handle_schema_ts() -> ([(HeaderName, &'static str); 1], String) {
+    (
+        [(CONTENT_TYPE, "application/typescript; charset=utf-8")],
+        schema::typescript(),
+    )
+}
+
+// /schemas/{name} -> JSON Schema of a response type
+async fn handle_json_schema(Path(name): Path<String>) -> Result<Json<schemars::Schema>, ApiError> {
+    let name = name.strip_suffix(".json").unwrap_or(&name);
+    schema::json_schema(name).map(Json).ok_or_else(|| {
+        ApiError::NotFound(format!(
+            "Unknown schema; expected one of {:?}",
+            schema::JSON_SCHEMAS
+        ))
+    })
+}
+
+// /header -> Returns the typed header properties, without decoding network frames
+async fn handle_header(
+    State(state): State<AppState>,
+    multipart: Result<Multipart, MultipartRejection>,
+) -> Result<Json<Properties>, ApiError> {
+    parse_multipart_replay(&state, multipart?, NetworkParse::Never, |replay| {
+        Ok(Json(Properties::try_from(replay.properties.as_slice())?))
+    })
+    .await
+}
+
+// /admin/catalog/reload -> Re-read the product catalog file
+async fn handle_catalog_reload(
+    State(state): State<AppState>,
+    headers: HeaderMap,
+) -> Result<Json<Value>, ApiError> {
+    check_admin(&headers)?;
+
+    let products = state.catalog.reload().map_err(ApiError::Internal)?;
+    // Cached parses carry product names from the previous catalog
+    let cache_purged = state.cache.purge();
+    Ok(Json(json!({
+        "path": state.catalog.path().display().to_string(),
+        "products": products,
+        "cache_purged": cache_purged,
+    })))
+}
+
+// /admin/cache -> Parse cache size and hit/miss counters
+async fn handle_cache_stats(
+    State(state): State<AppState>,
+    headers: HeaderMap,
+) -> Result<Json<CacheStats>, ApiError> {
+    check_admin(&headers)?;
+    Ok(Json(state.cache.stats()))
+}
+
+// /admin/cache/purge -> Drop every cached parse
+async fn handle_cache_purge(
+    State(state): State<AppState>,
+    headers: HeaderMap,
+) -> Result<Json<Value>, ApiError> {
+    check_admin(&headers)?;
+    Ok(Json(json!({ "purged": state.cache.purge() })))
+}
+
+// /admin/catalog/unknown -> Product IDs seen in replays but missing from the catalog
+async fn handle_catalog_unknown(
+    State(state): State<AppState>,
+    headers: HeaderMap,
+) -> Result<Json<Vec<UnknownProduct>>, ApiError> {
+    check_admin(&headers)?;
+    Ok(Json(state.catalog.unknown()))
+}
+
+// Admin routes require `Authorization: Bearer $ADMIN_TOKEN`, and are closed when
+// ADMIN_TOKEN is not set
+fn check_admin(headers: &HeaderMap) -> Result<(), ApiError> {
+    let token = match std::env::var("ADMIN_TOKEN") {
+        Ok(token) if !token.is_empty() => token,
+        _ => return Err(ApiError::Unauthorized),
+    };
+
+    let authorized = headers
+        .get("authorization")
+        .and_then(|v| v.to_str().ok())
+        .and_then(|v| v.strip_prefix("Bearer "))
+        .is_some_and(|v| v == token);
+
+    if authorized {
+        Ok(())
+    } else {
+        Err(ApiError::Unauthorized)
+    }
+}
+
 // /output & /output/basic -> Return raw Replay
 async fn handle_output(
-    mut multipart: Multipart,
+    state: AppState,
+    multipart: Result<Multipart, MultipartRejection>,
     parse_network: NetworkParse,
-) -> Result<Json<Replay>, (StatusCode, Json<Value>)> {
-    while let Some(field) = multipart.next_field().await.unwrap() {
-        let data = field.bytes().await.unwrap();
-        let parsed = ParserBuilder::new(&data)
-            .with_network_parse(parse_network)
-            .parse();
-
-        return parsed.map(Json).map_err(|e| {
-            (
-                StatusCode::INTERNAL_SERVER_ERROR,
-                Json(json!({ "error": e.to_string() })),
-            )
-        });
-    }
-
-    Err((
-        StatusCode::BAD_REQUEST,
-        Json(json!({ "error": "No replay file received" })),
-    ))
+) -> Result<Json<Replay>, ApiError> {
+    parse_multipart_replay(&state, multipart?, parse_network, |replay| Ok(Json(replay))).await
 }
 
-// Shared helper for parsing replay from multipart upload
+// Shared helper for parsing replay from multipart upload; decoding and `parser` run on
+// the parse pool so big replays don't hold up the async workers
 async fn parse_multipart_replay<T, F>(
-    mut multipart: Multipart,
+    state: &AppState,
+    multipart: Multipart,
+    parse_network: NetworkParse,
     parser: F,
-) -> Result<T, (StatusCode, Json<Value>)>
+) -> Result<T, ApiError>
 where
-    F: FnOnce(&Replay) -> Result<T, (StatusCode, Json<Value>)>,
+    F: FnOnce(Replay) -> Result<T, ApiError> + Send + 'static,
+    T: Send + 'static,
 {
-    while let Some(field) = multipart.next_field().await.unwrap() {
-        let data = field.bytes().await.unwrap();
-        match ParserBuilder::new(&data).parse() {
-            Ok(replay) => return parser(&replay),
-            Err(e) => {
-                return Err((
-                    StatusCode::INTERNAL_SERVER_ERROR,
-                    Json(json!({ "error": e.to_string() })),
-                ))
-            }
+    let data = read_replay_upload(multipart).await?;
+    state
+        .parser
+        .run(move || {
+            let replay = ParserBuilder::new(&data)
+                .with_network_parse(parse_network)
+                .parse()?;
+            parser(replay)
+        })
+        .await
+}
+
+// Ballchasing-style replay of an upload, reused from the cache when the same bytes were
+// parsed before
+async fn parse_ballchasing(state: &AppState, data: Bytes) -> Result<BallchasingReplay, ApiError> {
+    let mut replay = match state.cache.lookup(data, state.catalog.get()).await? {
+        Lookup::Hit(replay) => *replay,
+        Lookup::Miss(parse) => state.parser.run(parse).await?,
+    };
+    state.catalog.record_unknown(&replay);
+    state.matches.register(&mut replay);
+    Ok(replay)
+}
+
+// Bytes of the `file` field; other fields are skipped
+async fn read_replay_upload(mut multipart: Multipart) -> Result<Bytes, ApiError> {
+    let mut found = Vec::new();
+
+    while let Some(field) = multipart.next_field().await? {
+        let name = field.name().unwrap_or_default().to_string();
+        if name != UPLOAD_FIELD {
+            found.push(name);
+            continue;
         }
+
+        let data = field.bytes().await?;
+        if data.is_empty() {
+            return Err(ApiError::EmptyBody);
+        }
+        return Ok(data);
     }
 
-    Err((
-        StatusCode::BAD_REQUEST,
-        Json(json!({ "error": "No replay file received" })),
-    ))
+    if found.is_empty() {
+        Err(ApiError::EmptyBody)
+    } else {
+        Err(ApiError::WrongField { found })
+    }
 }
@@ -0,0 +1,170 @@
+use crate::types::{BallchasingReplay, SameMatchReplay};
+
+use serde::{Deserialize, Serialize};
+use std::collections::{HashMap, VecDeque};
+use std::fs::File;
+use std::io::{BufRead, BufReader, Write};
+use std::path::{Path, PathBuf};
+use std::sync::{Mutex, MutexGuard};
+
+/// Matches remembered when `MATCH_REGISTRY_SIZE` is not set
+const DEFAULT_MAX_MATCHES: usize = 10_000;
+
+/// Log of registered replays in `CACHE_DIR`, one JSON object per line
+const MATCHES_FILE: &str = "matches.jsonl";
+
+/// Recordings of the most recently seen `MATCH_REGISTRY_SIZE` matches, by `MatchGuid`, to
+/// spot several recordings of the same match; kept across restarts in `CACHE_DIR` when set
+pub struct MatchRegistry {
+    matches: Mutex<Matches>,
+    max_matches: usize,
+}
+
+/// Recordings by match, least recently seen first in `order`
+#[derive(Default)]
+struct Matches {
+    replays: HashMap<String, Vec<SameMatchReplay>>,
+    order: VecDeque<String>,
+    log: Option<File>,
+}
+
+/// A line of the matches log
+#[derive(Serialize, Deserialize)]
+struct Recording {
+    match_guid: String,
+    #[serde(flatten)]
+    replay: SameMatchReplay,
+}
+
+impl MatchRegistry {
+    /// Configure from `MATCH_REGISTRY_SIZE` and `CACHE_DIR`; the log is compacted to the
+    /// remembered matches on startup
+    pub fn from_env() -> Self {
+        let max_matches = std::env::var("MATCH_REGISTRY_SIZE")
+            .ok()
+            .and_then(|n| n.parse().ok())
+            .unwrap_or(DEFAULT_MAX_MATCHES);
+        let mut matches = Matches::default();
+
+        if let Ok(dir) = std::env::var("CACHE_DIR") {
+            let path = PathBuf::from(dir).join(MATCHES_FILE);
+            for recording in read_log(&path) {
+                matches.insert(recording.match_guid, recording.replay, max_matches);
+            }
+            match matches.compact(&path) {
+                Ok(log) => {
+                    matches.log = Some(log);
+                    println!(
+                        "🔗 Remembering {} matches from {}",
+                        matches.replays.len(),
+                        path.display()
+                    );
+                }
+                Err(e) => eprintln!("⚠️ Failed to write {}: {}", path.display(), e),
+            }
+        }
+
+        Self {
+            matches: Mutex::new(matches),
+            max_matches,
+        }
+    }
+
+    /// Remember the replay and list the other recordings of its match in `same_match`
+    pub fn register(&self, replay: &mut BallchasingReplay) {
+        if replay.match_guid.is_empty() {
+            return;
+        }
+
+        let this = SameMatchReplay {
+            id: replay.id.clone(),
+            recorded_by: replay.recorded_by.clone(),
+        };
+        let mut matches = self.matches();
+        replay.same_match = matches
+            .replays
+            .get(&replay.match_guid)
+            .map(|seen| seen.iter().filter(|r| r.id != this.id).cloned().collect())
+            .unwrap_or_default();
+
+        if matches.insert(replay.match_guid.clone(), this.clone(), self.max_matches) {
+            matches.append(Recording {
+                match_guid: replay.match_guid.clone(),
+                replay: this,
+            });
+        }
+    }
+
+    fn matches(&self) -> MutexGuard<'_, Matches> {
+        match self.matches.lock() {
+            Ok(matches) => matches,
+            Err(poisoned) => poisoned.into_inner(),
+        }
+    }
+}
+
+impl Matches {
+    /// Mark the match as most recently seen and add the recording; evicts the least
+    /// recently seen matches beyond `max_matches`. Returns whether the recording is new
+    fn insert(&mut self, match_guid: String, replay: SameMatchReplay, max_matches: usize) -> bool {
+        if let Some(position) = self.order.iter().position(|guid| *guid == match_guid) {
+            self.order.remove(position);
+        }
+        self.order.push_back(match_guid.clone());
+
+        let seen = self.replays.entry(match_guid).or_default();
+        let new = !seen.iter().any(|r| r.id == replay.id);
+        if new {
+            seen.push(replay);
+        }
+
+        while self.order.len() > max_matches {
+            if let Some(oldest) = self.order.pop_front() {
+                self.replays.remove(&oldest);
+            }
+        }
+        new
+    }
+
+    /// Add a line to the log, if there is one
+    fn append(&mut self, recording: Recording) {
+        let Some(log) = &mut self.log else {
+            return;
+        };
+        let written = serde_json::to_string(&recording)
+            .map_err(|e| e.to_string())
+            .and_then(|line| writeln!(log, "{}", line).map_err(|e| e.to_string()));
+        if let Err(e) = written {
+            eprintln!("⚠️ Failed to log match {}: {}", recording.match_guid, e);
+        }
+    }
+
+    /// Rewrite the log with the remembered matches only, and open it for appending
+    fn compact(&self, path: &Path) -> std::io::Result<File> {
+        let tmp = path.with_extension("tmp");
+        let mut file = File::create(&tmp)?;
+        for match_guid in &self.order {
+            for replay in &self.replays[match_guid] {
+                let recording = Recording {
+                    match_guid: match_guid.clone(),
+                    replay: replay.clone(),
+                };
+                writeln!(file, "{}", serde_json::to_string(&recording)?)?;
+            }
+        }
+        std::fs::rename(&tmp, path)?;
+        File::options().append(true).open(path)
+    }
+}
+
+/// Recordings of a matches log, oldest first; unreadable lines are skipped
+fn read_log(path: &Path) -> Vec<Recording> {
+    let Ok(file) = File::open(path) else {
+        return Vec::new();
+    };
+    BufReader::new(file)
+        .lines()
+        .map_while(Result::ok)
+        .filter_map(|line| serde_json::from_str(&line).ok())
+        .collect()
+}
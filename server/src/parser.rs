@@ -1,25 +1,32 @@
-use crate::helpers::{get_f32, get_i32, get_overtime_seconds, is_overtime};
+use crate::catalog::Catalog;
+use crate::dates::{parse_replay_date, source_timezone};
+use crate::helpers::{get_f32, get_i32, get_overtime_seconds, is_overtime, shooting_percentage};
+use crate::stats::{self, GameModeInfo, NetworkStats};
 use crate::types::ballchasing::PlayerId as BallchasingPlayerId;
-use crate::types::cars::get_car_map;
+use crate::types::{maps, playlists};
 use crate::types::{
-    BallchasingPlayer, BallchasingReplay, BallchasingTeam, BallchasingTeamStats, BoostStats,
-    CoreStats, DemoStats, PlayerStats,
+    BallchasingPlayer, BallchasingReplay, BallchasingTeam, BallchasingTeamStats, CoreStats,
+    DemoStats, PlayerBallStats, PlayerCamera, PlayerStats, UnresolvedValue,
 };
 
-use boxcars::{Attribute, Frame, HeaderProp, Replay};
-use chrono::Utc;
+use boxcars::{HeaderProp, Replay};
+use chrono::DateTime;
+use std::cmp::Ordering;
 use std::collections::HashMap;
-use uuid::Uuid;
+use uuid::{uuid, Uuid};
 
-/// Parse the replay into a Ballchasing-style object
-pub fn parse_to_ballchasing(replay: &Replay) -> BallchasingReplay {
+/// Namespace of the version 5 UUIDs used as replay IDs
+const REPLAY_ID_NAMESPACE: Uuid = uuid!("6f1c2a0e-5b7d-4c53-9a8e-2d41f0b7c3e9");
+
+/// Parse the replay into a Ballchasing-style object; `content_hash` is the SHA-256 of
+/// the uploaded file
+pub fn parse_to_ballchasing(
+    replay: &Replay,
+    catalog: &Catalog,
+    content_hash: &str,
+) -> BallchasingReplay {
     let props = &replay.properties;
-    let frames = replay
-        .network_frames
-        .as_ref()
-        .map(|nf| nf.frames.as_slice())
-        .unwrap_or(&[]);
-    let car_id_map = get_car_ids_by_name(frames);
+    let network = stats::analyze(replay);
 
     let get = |key: &str| {
         props
@@ -29,14 +36,46 @@ pub fn parse_to_ballchasing(replay: &Replay) -> BallchasingReplay {
             .unwrap_or("")
     };
 
-    let id = Uuid::new_v4().to_string();
-    let created = Utc::now().to_rfc3339();
+    let id = replay_id(get("MatchGuid"), get("Id"), content_hash);
+    // When the match started, so parsing the same file twice gives the same replay
+    let match_start = get("MatchStartEpoch").parse().ok();
+    let created = match_start
+        .and_then(|epoch| DateTime::from_timestamp(epoch, 0))
+        .map(|date| date.to_rfc3339())
+        .unwrap_or_default();
+    let recorded_by = Some(get("PlayerName"))
+        .filter(|name| !name.is_empty())
+        .map(str::to_string);
+
+    let mut unresolved = Vec::new();
+    let team_size = get_i32(props, "TeamSize") as u8;
+    let (map_name, map_variant) = resolve_map(get("MapName"), &mut unresolved);
+    let playlist = resolve_playlist(
+        get("MatchType"),
+        team_size,
+        &network.game_mode,
+        &mut unresolved,
+    );
 
     let duration = get_f32(props, "TotalSecondsPlayed") as f64;
     let overtime = is_overtime(props);
     let overtime_seconds = get_overtime_seconds(duration, overtime);
+    let date = parse_replay_date(get("Date"), match_start, duration, source_timezone());
+    if date.is_none() && !get("Date").is_empty() {
+        unresolved.push(UnresolvedValue {
+            field: "date".into(),
+            value: get("Date").into(),
+        });
+    }
 
-    let all_players = parse_players(props, &car_id_map);
+    let all_players = parse_players(props, &network, catalog);
+    // The real score; own goals aren't credited to any player
+    let blue_goals = get_i32(props, "Team0Score") as u32;
+    let orange_goals = get_i32(props, "Team1Score") as u32;
+    let mut blue = build_team("blue", all_players.as_slice(), 0, blue_goals, &network);
+    let mut orange = build_team("orange", all_players.as_slice(), 1, orange_goals, &network);
+    apply_opponent_stats(&mut blue, &orange);
+    apply_opponent_stats(&mut orange, &blue);
 
     BallchasingReplay {
         id,
@@ -47,23 +86,132 @@ pub fn parse_to_ballchasing(replay: &Replay) -> BallchasingReplay {
         title: get("ReplayName").into(),
         map_code: get("MapName").into(),
         match_type: get("MatchType").into(),
-        team_size: get_i32(props, "TeamSize") as u8,
-        playlist_id: get("MatchType").into(),
+        team_size,
+        playlist_id: playlist.map(|(id, _)| id.to_string()),
         duration,
         overtime,
         overtime_seconds,
-        date: get("Date").into(),
-        blue: build_team("blue", all_players.as_slice(), 0),
-        orange: build_team("orange", all_players.as_slice(), 1),
-        playlist_name: get("MatchType").into(),
-        map_name: get("MapName").into(),
+        date_has_timezone: date.as_ref().is_some_and(|date| date.has_timezone),
+        date: date.map(|date| date.date).unwrap_or_default(),
+        blue,
+        orange,
+        playlist_name: playlist.map(|(_, name)| name.to_string()),
+        map_name,
+        map_variant,
+        goals: network.goals,
+        content_hash: content_hash.to_string(),
+        recorded_by,
+        same_match: Vec::new(),
+        unresolved,
+    }
+}
+
+/// Display name and variant of a map code, matched case-insensitively
+fn resolve_map(
+    code: &str,
+    unresolved: &mut Vec<UnresolvedValue>,
+) -> (Option<String>, Option<String>) {
+    match maps::MAP_NAMES.get(code.to_lowercase().as_str()) {
+        Some((name, Some(variant))) => (
+            Some(format!("{} ({})", name, variant)),
+            Some(variant.to_string()),
+        ),
+        Some((name, None)) => (Some(name.to_string()), None),
+        None => {
+            if !code.is_empty() {
+                unresolved.push(UnresolvedValue {
+                    field: "map_code".into(),
+                    value: code.into(),
+                });
+            }
+            (None, None)
+        }
+    }
+}
+
+/// Playlist from the replicated playlist ID, else from the header `MatchType`; online
+/// matches without the attribute can't be told ranked from casual, so they are reported
+fn resolve_playlist(
+    match_type: &str,
+    team_size: u8,
+    game_mode: &GameModeInfo,
+    unresolved: &mut Vec<UnresolvedValue>,
+) -> Option<(&'static str, &'static str)> {
+    if let Some(id) = game_mode.playlist {
+        if let Some(playlist) = playlists::PLAYLISTS.get(&id) {
+            return Some(*playlist);
+        }
+        unresolved.push(UnresolvedValue {
+            field: "playlist".into(),
+            value: id.to_string(),
+        });
+    }
+
+    if game_mode.private_match {
+        return playlists::match_type_playlist("Private");
+    }
+    if let Some(playlist) = playlists::match_type_playlist(match_type) {
+        return Some(playlist);
+    }
+
+    if game_mode.playlist.is_none() {
+        let mode = match game_mode.ball.as_deref() {
+            Some("Archetypes.Ball.Ball_Basketball") => "hoops",
+            Some("Archetypes.Ball.Ball_Puck") => "snowday",
+            Some("Archetypes.Ball.Ball_Breakout") => "dropshot",
+            _ if game_mode.rumble => "rumble",
+            _ => "soccar",
+        };
+        let game_mode_index = game_mode
+            .game_mode
+            .map_or(String::new(), |index| format!(", game mode {}", index));
+        unresolved.push(UnresolvedValue {
+            field: "playlist".into(),
+            value: format!(
+                "{} {}v{} {}{}",
+                match_type, team_size, team_size, mode, game_mode_index
+            ),
+        });
     }
+    None
+}
+
+/// Same for every upload of the same file; two recordings of one match share the
+/// `MatchGuid` but not the content hash, so they get different IDs
+fn replay_id(match_guid: &str, rocket_league_id: &str, content_hash: &str) -> String {
+    let name = if match_guid.is_empty() {
+        rocket_league_id
+    } else {
+        match_guid
+    };
+    Uuid::new_v5(
+        &REPLAY_ID_NAMESPACE,
+        format!("{}:{}", name, content_hash).as_bytes(),
+    )
+    .to_string()
+}
+
+/// Camera profile and usage of every player, blue team first
+pub fn camera_summary(replay: &BallchasingReplay) -> Vec<PlayerCamera> {
+    [&replay.blue, &replay.orange]
+        .into_iter()
+        .flat_map(|team| {
+            team.players.iter().map(|p| PlayerCamera {
+                name: p.name.clone(),
+                id: p.id.clone(),
+                team: team.color.clone(),
+                camera: p.camera.clone(),
+                stats: p.stats.camera.clone(),
+            })
+        })
+        .collect()
 }
 
-/// Parse all players from PlayerStats and mark global MVP
+/// Parse all players from PlayerStats and mark the MVP
 fn parse_players(
     props: &[(String, HeaderProp)],
-    car_ids: &HashMap<String, (u32, String)>,
+    network: &NetworkStats,
+    catalog: &Catalog,
 ) -> Vec<(i32, i32, BallchasingPlayer)> {
     let binding = vec![];
     let players_raw = props
@@ -83,45 +231,53 @@ fn parse_players(
             .unwrap_or("Unknown")
             .to_string();
 
-        let (platform, platform_id) = get("PlayerID")
-            .and_then(|v| match v {
-                HeaderProp::Struct { fields, .. } => {
-                    let uid = fields
-                        .iter()
-                        .find(|(k, _)| k == "Uid")
-                        .and_then(|(_, v)| match v {
-                            HeaderProp::QWord(q) => Some(q.to_string()),
-                            _ => None,
-                        });
-
-                    let platform_raw =
-                        fields
+        let (platform, platform_id) =
+            get("PlayerID")
+                .and_then(|v| match v {
+                    HeaderProp::Struct { fields, .. } => {
+                        // Epic players have a zero `Uid` and carry their ID in `EpicAccountId`
+                        let uid = fields
                             .iter()
-                            .find(|(k, _)| k == "Platform")
+                            .find(|(k, _)| k == "Uid")
                             .and_then(|(_, v)| match v {
+                                HeaderProp::QWord(q) if *q != 0 => Some(q.to_string()),
+                                _ => None,
+                            })
+                            .or_else(|| {
+                                fields
+                                    .iter()
+                                    .find(|(k, _)| k == "EpicAccountId")
+                                    .and_then(|(_, v)| v.as_string())
+                                    .filter(|id| !id.is_empty())
+                                    .map(str::to_string)
+                            });
+
+                        let platform_raw = fields.iter().find(|(k, _)| k == "Platform").and_then(
+                            |(_, v)| match v {
                                 HeaderProp::Byte {
                                     value: Some(val), ..
                                 } => Some(val.clone()),
                                 _ => None,
-                            });
+                            },
+                        );
 
-                    let platform = match platform_raw.as_deref() {
-                        Some("OnlinePlatform_Steam") => "steam",
-                        Some("OnlinePlatform_Epic") => "epic",
-                        Some("OnlinePlatform_PS4") => "ps4",
-                        Some("OnlinePlatform_PS5") => "ps5",
-                        Some("OnlinePlatform_Xbox") => "xbox",
-                        Some("OnlinePlatform_Switch") => "switch",
-                        Some(other) => other,
-                        None => "unknown",
-                    }
-                    .to_string();
+                        let platform = match platform_raw.as_deref() {
+                            Some("OnlinePlatform_Steam") => "steam",
+                            Some("OnlinePlatform_Epic") => "epic",
+                            Some("OnlinePlatform_PS4") => "ps4",
+                            Some("OnlinePlatform_PS5") => "ps5",
+                            Some("OnlinePlatform_Xbox") => "xbox",
+                            Some("OnlinePlatform_Switch") => "switch",
+                            Some(other) => other,
+                            None => "unknown",
+                        }
+                        .to_string();
 
-                    Some((platform, uid.unwrap_or_else(|| "unknown".to_string())))
-                }
-                _ => None,
-            })
-            .unwrap_or_else(|| ("unknown".to_string(), "unknown".to_string()));
+                        Some((platform, uid.unwrap_or_else(|| "unknown".to_string())))
+                    }
+                    _ => None,
+                })
+                .unwrap_or_else(|| ("unknown".to_string(), "unknown".to_string()));
 
         let score = get("Score").and_then(|v| v.as_i32()).unwrap_or(0);
         let goals = get("Goals").and_then(|v| v.as_i32()).unwrap_or(0);
@@ -129,16 +285,32 @@ fn parse_players(
         let assists = get("Assists").and_then(|v| v.as_i32()).unwrap_or(0);
         let saves = get("Saves").and_then(|v| v.as_i32()).unwrap_or(0);
 
-        let shooting_percentage = if shots > 0 {
-            (goals as f32 / shots as f32 * 100.0).round() as u32
-        } else {
-            0
-        };
-
-        let (car_id, car_name) = car_ids
-            .get(&name)
+        let key = stats::player_key(Some(&platform_id), &name);
+        let cosmetics = lookup(&network.loadouts, &key, &name);
+        let car_id = cosmetics.map_or(0, |c| c.loadout.body);
+        let car_name = catalog.name(car_id).unwrap_or("Unknown").to_string();
+        let loadout = cosmetics
+            .map(|c| stats::player_loadout(c, catalog))
+            .unwrap_or_default();
+        let possession_time = lookup(&network.possession, &key, &name)
+            .copied()
+            .unwrap_or_default();
+        let boost = lookup(&network.boost, &key, &name)
+            .cloned()
+            .unwrap_or_default();
+        let movement = lookup(&network.movement, &key, &name)
             .cloned()
-            .unwrap_or_else(|| (0, "Unknown".to_string()));
+            .unwrap_or_default();
+        let positioning = lookup(&network.positioning, &key, &name)
+            .cloned()
+            .unwrap_or_default();
+        let demo = lookup(&network.demos, &key, &name)
+            .cloned()
+            .unwrap_or_default();
+        let camera = lookup(&network.camera_settings, &key, &name).cloned();
+        let camera_stats = lookup(&network.camera, &key, &name)
+            .cloned()
+            .unwrap_or_default();
 
         let player = BallchasingPlayer {
             name,
@@ -148,44 +320,94 @@ fn parse_players(
             },
             car_id,
             car_name,
+            loadout,
+            camera,
             stats: PlayerStats {
+                ball: PlayerBallStats { possession_time },
                 core: CoreStats {
                     shots: shots as u32,
+                    shots_against: 0,
                     goals: goals as u32,
+                    goals_against: 0,
                     saves: saves as u32,
                     assists: assists as u32,
                     score: score as u32,
                     mvp: false,
-                    shooting_percentage,
-                },
-                boost: BoostStats { bpm: 0 },
-                demo: DemoStats {
-                    inflicted: 0,
-                    taken: 0,
+                    shooting_percentage: shooting_percentage(goals as u32, shots as u32),
                 },
+                boost,
+                movement,
+                positioning,
+                demo,
+                camera: camera_stats,
             },
         };
 
         players.push((score, team, player));
     }
 
-    if let Some(index) = players
-        .iter()
-        .enumerate()
-        .max_by_key(|(_, (score, _, _))| *score)
-        .map(|(i, _)| i)
-    {
-        players[index].2.stats.core.mvp = true;
+    if let Some(winner) = winning_team(props, network) {
+        mark_mvp(&mut players, winner);
     }
 
     players
 }
 
-/// Group players by team and return BallchasingTeam
+/// Team index of the winner, or `None` for draws and forfeits
+fn winning_team(props: &[(String, HeaderProp)], network: &NetworkStats) -> Option<i32> {
+    if network.forfeit {
+        return None;
+    }
+
+    // A team that didn't score has no `TeamNScore` entry at all
+    let has_score = |key: &str| props.iter().any(|(k, _)| k == key);
+    let (blue, orange) = if has_score("Team0Score") || has_score("Team1Score") {
+        (get_i32(props, "Team0Score"), get_i32(props, "Team1Score"))
+    } else {
+        network
+            .goals
+            .last()
+            .map(|g| (g.score.blue as i32, g.score.orange as i32))
+            .unwrap_or((0, 0))
+    };
+
+    match blue.cmp(&orange) {
+        Ordering::Greater => Some(0),
+        Ordering::Less => Some(1),
+        Ordering::Equal => None,
+    }
+}
+
+/// MVP is the top scorer on the winning team. Ties go to goals, then assists,
+/// saves and shots, and finally the player name so the pick is deterministic.
+fn mark_mvp(players: &mut [(i32, i32, BallchasingPlayer)], winner: i32) {
+    let mvp = players
+        .iter_mut()
+        .filter(|(_, team, _)| *team == winner)
+        .max_by(|(a_score, _, a), (b_score, _, b)| {
+            let (a_core, b_core) = (&a.stats.core, &b.stats.core);
+            a_score
+                .cmp(b_score)
+                .then(a_core.goals.cmp(&b_core.goals))
+                .then(a_core.assists.cmp(&b_core.assists))
+                .then(a_core.saves.cmp(&b_core.saves))
+                .then(a_core.shots.cmp(&b_core.shots))
+                .then_with(|| b.name.cmp(&a.name))
+        });
+
+    if let Some((_, _, player)) = mvp {
+        player.stats.core.mvp = true;
+    }
+}
+
+/// Group players by team and return BallchasingTeam; `goals` is the team's score, the
+/// other core stats are summed over its players
 fn build_team(
     color: &str,
     all_players: &[(i32, i32, BallchasingPlayer)],
     team_index: i32,
+    goals: u32,
+    network: &NetworkStats,
 ) -> BallchasingTeam {
     let players = all_players
         .iter()
@@ -193,53 +415,178 @@ fn build_team(
         .map(|(_, _, p)| (*p).clone())
         .collect::<Vec<_>>();
 
+    let core = players
+        .iter()
+        .fold(CoreStats::default(), |acc, p| CoreStats {
+            shots: acc.shots + p.stats.core.shots,
+            saves: acc.saves + p.stats.core.saves,
+            assists: acc.assists + p.stats.core.assists,
+            score: acc.score + p.stats.core.score,
+            ..acc
+        });
+
+    let demo = players
+        .iter()
+        .fold(DemoStats::default(), |acc, p| DemoStats {
+            inflicted: acc.inflicted + p.stats.demo.inflicted,
+            taken: acc.taken + p.stats.demo.taken,
+        });
+
+    let boost = stats::team_boost(players.iter().map(|p| &p.stats.boost));
+    let movement = stats::team_movement(players.iter().map(|p| &p.stats.movement));
+    let positioning = stats::team_positioning(players.iter().map(|p| &p.stats.positioning));
+
     BallchasingTeam {
         color: color.into(),
         name: format!("{} team", color),
         players,
         stats: BallchasingTeamStats {
+            ball: network.ball[team_index as usize].clone(),
             core: CoreStats {
-                shots: 0,
-                goals: 0,
-                saves: 0,
-                assists: 0,
-                score: 0,
-                mvp: false,
-                shooting_percentage: 0,
-            },
-            demo: DemoStats {
-                inflicted: 0,
-                taken: 0,
+                goals,
+                shooting_percentage: shooting_percentage(goals, core.shots),
+                ..core
             },
+            boost,
+            movement,
+            positioning,
+            demo,
         },
     }
 }
 
-/// Match car ID by player name from TeamLoadout attribute
-pub fn get_car_ids_by_name(frames: &[Frame]) -> HashMap<String, (u32, String)> {
-    let mut car_ids: HashMap<String, (u32, String)> = HashMap::new();
-    let car_map = get_car_map();
-
-    for frame in frames {
-        for actor in &frame.updated_actors {
-            if let Attribute::TeamLoadout(loadout) = &actor.attribute {
-                // Try to find the corresponding name from the same actor
-                let name_attr = frame.updated_actors.iter().find(|a| {
-                    a.actor_id == actor.actor_id && matches!(&a.attribute, Attribute::String(_))
-                });
+/// Fill shots and goals against, for the team and each of its players, from the opposing team
+fn apply_opponent_stats(team: &mut BallchasingTeam, opponent: &BallchasingTeam) {
+    let shots_against = opponent.stats.core.shots;
+    let goals_against = opponent.stats.core.goals;
 
-                if let Some(Attribute::String(player_name)) = name_attr.map(|a| &a.attribute) {
-                    let body_id = loadout.blue.body; // you can also choose orange if needed
-                    let car_name = car_map
-                        .get(&(body_id as u32))
-                        .unwrap_or(&"Unknown")
-                        .to_string();
+    team.stats.core.shots_against = shots_against;
+    team.stats.core.goals_against = goals_against;
 
-                    car_ids.insert(player_name.clone(), (body_id as u32, car_name));
-                }
-            }
+    for player in &mut team.players {
+        player.stats.core.shots_against = shots_against;
+        player.stats.core.goals_against = goals_against;
+    }
+}
+
+/// Network stats of a player, falling back to their name for replays that
+/// never replicate a `UniqueId`
+fn lookup<'a, T>(map: &'a HashMap<String, T>, key: &str, name: &str) -> Option<&'a T> {
+    map.get(key).or_else(|| map.get(name))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::types::{GoalEvent, Scoreline};
+
+    fn scores(blue: Option<i32>, orange: Option<i32>) -> Vec<(String, HeaderProp)> {
+        [("Team0Score", blue), ("Team1Score", orange)]
+            .into_iter()
+            .filter_map(|(key, score)| Some((key.to_string(), HeaderProp::Int(score?))))
+            .collect()
+    }
+
+    fn player(name: &str, team: i32, score: i32, goals: u32) -> (i32, i32, BallchasingPlayer) {
+        let player = BallchasingPlayer {
+            name: name.into(),
+            id: BallchasingPlayerId {
+                platform: "steam".into(),
+                id: name.into(),
+            },
+            car_id: 0,
+            car_name: String::new(),
+            loadout: Default::default(),
+            camera: None,
+            stats: PlayerStats {
+                ball: Default::default(),
+                core: CoreStats {
+                    goals,
+                    score: score as u32,
+                    ..Default::default()
+                },
+                boost: Default::default(),
+                movement: Default::default(),
+                positioning: Default::default(),
+                demo: Default::default(),
+                camera: Default::default(),
+            },
+        };
+        (score, team, player)
+    }
+
+    fn mvps(players: &[(i32, i32, BallchasingPlayer)]) -> Vec<&str> {
+        players
+            .iter()
+            .filter(|(_, _, p)| p.stats.core.mvp)
+            .map(|(_, _, p)| p.name.as_str())
+            .collect()
+    }
+
+    fn goal(blue: u32, orange: u32) -> GoalEvent {
+        GoalEvent {
+            frame: 0,
+            time: 0.0,
+            seconds_remaining: None,
+            overtime: false,
+            team: String::new(),
+            scorer: String::new(),
+            assister: None,
+            score: Scoreline { blue, orange },
+            ball_speed: 0.0,
+            ball_location: None,
         }
     }
 
-    car_ids
+    #[test]
+    fn winner_from_header_score() {
+        let network = NetworkStats::default();
+        assert_eq!(winning_team(&scores(Some(3), Some(1)), &network), Some(0));
+        // A team that didn't score has no key
+        assert_eq!(winning_team(&scores(None, Some(2)), &network), Some(1));
+    }
+
+    #[test]
+    fn draws_and_forfeits_have_no_winner() {
+        let network = NetworkStats::default();
+        assert_eq!(winning_team(&scores(Some(2), Some(2)), &network), None);
+        assert_eq!(winning_team(&scores(None, None), &network), None);
+
+        let forfeit = NetworkStats {
+            forfeit: true,
+            ..Default::default()
+        };
+        assert_eq!(winning_team(&scores(Some(3), Some(0)), &forfeit), None);
+    }
+
+    #[test]
+    fn winner_from_goal_timeline_without_score_keys() {
+        let network = NetworkStats {
+            goals: vec![goal(0, 1), goal(1, 1), goal(1, 2)],
+            ..Default::default()
+        };
+        assert_eq!(winning_team(&scores(None, None), &network), Some(1));
+    }
+
+    #[test]
+    fn loser_with_top_score_is_not_mvp() {
+        let mut players = vec![
+            player("Blue 1", 0, 500, 1),
+            player("Blue 2", 0, 300, 1),
+            player("Orange 1", 1, 900, 1),
+        ];
+        mark_mvp(&mut players, 0);
+        assert_eq!(mvps(&players), ["Blue 1"]);
+    }
+
+    #[test]
+    fn equal_scores_go_to_goals_then_name() {
+        let mut players = vec![player("Alpha", 0, 500, 1), player("Bravo", 0, 500, 2)];
+        mark_mvp(&mut players, 0);
+        assert_eq!(mvps(&players), ["Bravo"]);
+
+        let mut players = vec![player("Bravo", 0, 500, 1), player("Alpha", 0, 500, 1)];
+        mark_mvp(&mut players, 0);
+        assert_eq!(mvps(&players), ["Alpha"]);
+    }
 }
@@ -0,0 +1,155 @@
+use crate::error::ApiError;
+
+use std::sync::Arc;
+use tokio::sync::{OwnedSemaphorePermit, Semaphore};
+
+/// Seconds clients are told to wait when the queue is full, unless `PARSE_RETRY_AFTER` is set
+const DEFAULT_RETRY_AFTER: u64 = 5;
+
+/// Parses waiting for a worker, unless `PARSE_QUEUE_SIZE` is set
+const DEFAULT_QUEUE_SIZE: usize = 32;
+
+/// Runs replay parsing on tokio's blocking pool, at most `workers` at a time; up to
+/// `queue_size` more requests wait for a worker and the rest are turned away
+pub struct ParsePool {
+    /// One permit per running or queued parse
+    admission: Arc<Semaphore>,
+    /// One permit per running parse
+    workers: Arc<Semaphore>,
+    workers_count: usize,
+    queue_size: usize,
+    retry_after: u64,
+}
+
+impl ParsePool {
+    pub fn new(workers: usize, queue_size: usize, retry_after: u64) -> Self {
+        let workers = workers.max(1);
+        Self {
+            admission: Arc::new(Semaphore::new(workers + queue_size)),
+            workers: Arc::new(Semaphore::new(workers)),
+            workers_count: workers,
+            queue_size,
+            retry_after,
+        }
+    }
+
+    /// Configure from `MAX_CONCURRENT_PARSES` (default: CPU count), `PARSE_QUEUE_SIZE`
+    /// and `PARSE_RETRY_AFTER`
+    pub fn from_env() -> Self {
+        let env = |key: &str| std::env::var(key).ok().and_then(|v| v.parse().ok());
+        let cpus = std::thread::available_parallelism().map_or(1, |n| n.get());
+
+        let pool = Self::new(
+            env("MAX_CONCURRENT_PARSES").unwrap_or(cpus),
+            env("PARSE_QUEUE_SIZE").unwrap_or(DEFAULT_QUEUE_SIZE),
+            env("PARSE_RETRY_AFTER")
+                .map(|s: usize| s as u64)
+                .unwrap_or(DEFAULT_RETRY_AFTER),
+        );
+        println!(
+            "⚙️ Parsing up to {} replays at once, {} queued",
+            pool.workers_count, pool.queue_size
+        );
+        pool
+    }
+
+    /// Run `parse` on the blocking pool once a worker is free; fails right away with
+    /// `ApiError::Busy` when every worker and queue slot is taken
+    pub async fn run<T, F>(&self, parse: F) -> Result<T, ApiError>
+    where
+        F: FnOnce() -> Result<T, ApiError> + Send + 'static,
+        T: Send + 'static,
+    {
+        let _admitted = self.admit()?;
+        on_worker(self.workers.clone(), parse).await
+    }
+
+    /// Run `parse` once a worker is free, without taking a queue slot; for work that is
+    /// already queued elsewhere, like background jobs
+    pub async fn run_queued<T, F>(&self, parse: F) -> Result<T, ApiError>
+    where
+        F: FnOnce() -> Result<T, ApiError> + Send + 'static,
+        T: Send + 'static,
+    {
+        on_worker(self.workers.clone(), parse).await
+    }
+
+    /// Number of parses that run at once
+    pub fn workers(&self) -> usize {
+        self.workers_count
+    }
+
+    /// Seconds clients are told to wait when turned away
+    pub fn retry_after(&self) -> u64 {
+        self.retry_after
+    }
+
+    /// Parses that can be running or queued at once
+    pub fn capacity(&self) -> usize {
+        self.workers_count + self.queue_size
+    }
+
+    /// Run every parse of a batch, as many at once as there are workers; the batch takes
+    /// one queue slot per replay and is turned away unless all of them are free
+    pub async fn run_batch<T, F>(
+        &self,
+        parses: Vec<F>,
+    ) -> Result<Vec<Result<T, ApiError>>, ApiError>
+    where
+        F: FnOnce() -> Result<T, ApiError> + Send + 'static,
+        T: Send + 'static,
+    {
+        if parses.len() > self.capacity() {
+            return Err(ApiError::TooManyFiles {
+                limit: self.capacity(),
+            });
+        }
+        let _admitted = self.admit_many(parses.len())?;
+
+        let handles: Vec<_> = parses
+            .into_iter()
+            .map(|parse| tokio::spawn(on_worker(self.workers.clone(), parse)))
+            .collect();
+
+        let mut results = Vec::with_capacity(handles.len());
+        for handle in handles {
+            results.push(handle.await.unwrap_or_else(|e| Err(crashed(e))));
+        }
+        Ok(results)
+    }
+
+    fn admit(&self) -> Result<OwnedSemaphorePermit, ApiError> {
+        self.admit_many(1)
+    }
+
+    fn admit_many(&self, count: usize) -> Result<OwnedSemaphorePermit, ApiError> {
+        self.admission
+            .clone()
+            .try_acquire_many_owned(count as u32)
+            .map_err(|_| ApiError::Busy {
+                retry_after: self.retry_after,
+            })
+    }
+}
+
+async fn on_worker<T, F>(workers: Arc<Semaphore>, parse: F) -> Result<T, ApiError>
+where
+    F: FnOnce() -> Result<T, ApiError> + Send + 'static,
+    T: Send + 'static,
+{
+    let worker = workers
+        .acquire_owned()
+        .await
+        .map_err(|e| ApiError::Internal(e.to_string()))?;
+
+    tokio::task::spawn_blocking(move || {
+        let _worker = worker;
+        parse()
+    })
+    .await
+    .map_err(crashed)?
+}
+
+fn crashed(e: tokio::task::JoinError) -> ApiError {
+    ApiError::Internal(format!("Replay parser crashed: {}", e))
+}
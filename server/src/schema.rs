@@ -0,0 +1,436 @@
+use crate::batch::BatchResult;
+use crate::cache::CacheStats;
+use crate::catalog::UnknownProduct;
+use crate::error::ApiError;
+use crate::jobs::{CallbackStatus, Job, JobError, JobReport, JobStatus};
+use crate::types::ballchasing::*;
+use crate::types::{Location, Properties, Root};
+
+use schemars::generate::SchemaSettings;
+use schemars::{JsonSchema, Schema};
+use serde_json::{json, Value};
+use std::collections::BTreeMap;
+use ts_rs::{Config, TS};
+
+/// Response types with a standalone JSON Schema under `/schemas/{name}`
+pub const JSON_SCHEMAS: [&str; 7] = [
+    "BallchasingReplay",
+    "BatchResult",
+    "JobReport",
+    "PlayerCamera",
+    "Properties",
+    "Root",
+    "UnknownProduct",
+];
+
+/// Where the Next.js app imports the generated declarations from
+#[cfg(test)]
+const TYPESCRIPT_PATH: &str = concat!(env!("CARGO_MANIFEST_DIR"), "/../src/types/parser.ts");
+
+/// TypeScript declarations of every type returned by `/parse`, `/parse/batch`, `/jobs`
+/// and `/camera`
+pub fn typescript() -> String {
+    let cfg = Config::new();
+    let declarations = [
+        BallchasingReplay::decl(&cfg),
+        BatchResult::decl(&cfg),
+        Job::decl(&cfg),
+        JobReport::decl(&cfg),
+        JobStatus::decl(&cfg),
+        CallbackStatus::decl(&cfg),
+        JobError::decl(&cfg),
+        BallchasingTeam::decl(&cfg),
+        BallchasingPlayer::decl(&cfg),
+        PlayerId::decl(&cfg),
+        CameraSettings::decl(&cfg),
+        PlayerLoadout::decl(&cfg),
+        LoadoutItem::decl(&cfg),
+        ProductAttribute::decl(&cfg),
+        CarPaint::decl(&cfg),
+        PlayerStats::decl(&cfg),
+        BallchasingTeamStats::decl(&cfg),
+        PlayerBallStats::decl(&cfg),
+        BallStats::decl(&cfg),
+        CoreStats::decl(&cfg),
+        BoostStats::decl(&cfg),
+        MovementStats::decl(&cfg),
+        PositioningStats::decl(&cfg),
+        DemoStats::decl(&cfg),
+        CameraStats::decl(&cfg),
+        PlayerCamera::decl(&cfg),
+        SameMatchReplay::decl(&cfg),
+        UnresolvedValue::decl(&cfg),
+        GoalEvent::decl(&cfg),
+        Scoreline::decl(&cfg),
+        Location::decl(&cfg),
+    ];
+
+    let mut out = String::from(
+        "// Generated from server/src/types by `UPDATE_SCHEMA=1 cargo test`. Do not edit.\n",
+    );
+    for declaration in declarations {
+        out.push_str("\nexport ");
+        out.push_str(&declaration);
+        out.push('\n');
+    }
+    out
+}
+
+/// JSON Schema (draft 2020-12) of a response type listed in `JSON_SCHEMAS`
+pub fn json_schema(name: &str) -> Option<Schema> {
+    let generator = SchemaSettings::draft2020_12().into_generator();
+    let schema = match name {
+        "BallchasingReplay" => generator.into_root_schema_for::<BallchasingReplay>(),
+        "BatchResult" => generator.into_root_schema_for::<BatchResult>(),
+        "JobReport" => generator.into_root_schema_for::<JobReport>(),
+        "PlayerCamera" => generator.into_root_schema_for::<PlayerCamera>(),
+        "Properties" => generator.into_root_schema_for::<Properties>(),
+        "Root" => generator.into_root_schema_for::<Root>(),
+        "UnknownProduct" => generator.into_root_schema_for::<UnknownProduct>(),
+        _ => return None,
+    };
+    Some(schema)
+}
+
+/// Schema of `T` that refers to `#/components/schemas`
+fn reference<T: JsonSchema>(generator: &mut schemars::SchemaGenerator) -> Value {
+    generator.subschema_for::<T>().to_value()
+}
+
+/// OpenAPI 3 description of every route
+pub fn openapi() -> Value {
+    let mut generator = SchemaSettings::openapi3().into_generator();
+    let replay = reference::<BallchasingReplay>(&mut generator);
+    let batch = reference::<BTreeMap<String, BatchResult>>(&mut generator);
+    let job = reference::<Job>(&mut generator);
+    let job_report = reference::<JobReport>(&mut generator);
+    let cameras = reference::<Vec<PlayerCamera>>(&mut generator);
+    let properties = reference::<Properties>(&mut generator);
+    let root = reference::<Root>(&mut generator);
+    let unknown = reference::<Vec<UnknownProduct>>(&mut generator);
+    let cache_stats = reference::<CacheStats>(&mut generator);
+
+    let mut schemas = serde_json::Map::from_iter(generator.take_definitions(true));
+    schemas.insert(
+        "Error".into(),
+        json!({
+            "type": "object",
+            "required": ["error", "code"],
+            "properties": {
+                "error": { "type": "string" },
+                "code": { "type": "string", "enum": ApiError::CODES },
+                "field": {
+                    "type": "string",
+                    "description": "Header property that failed validation (/header)"
+                }
+            }
+        }),
+    );
+
+    let upload = json!({
+        "required": true,
+        "content": {
+            "multipart/form-data": {
+                "schema": {
+                    "type": "object",
+                    "required": ["file"],
+                    "properties": {
+                        "file": {
+                            "type": "string",
+                            "format": "binary",
+                            "description": "A .replay file; other fields are ignored"
+                        }
+                    }
+                }
+            }
+        }
+    });
+    let error = |description: &str| {
+        json!({
+            "description": description,
+            "content": {
+                "application/json": { "schema": { "$ref": "#/components/schemas/Error" } }
+            }
+        })
+    };
+    let ok = |description: &str, schema: Value| {
+        json!({
+            "description": description,
+            "content": { "application/json": { "schema": schema } }
+        })
+    };
+    let upload_route = |summary: &str, response: Value, extra: &[(&str, Value)]| {
+        let mut responses = serde_json::Map::new();
+        responses.insert("200".into(), response);
+        responses.insert(
+            "400".into(),
+            error("bad_multipart, empty_body or wrong_field: no usable replay in the form"),
+        );
+        responses.insert(
+            "413".into(),
+            error("file_too_large: the upload exceeds MAX_UPLOAD_MB"),
+        );
+        responses.insert(
+            "415".into(),
+            error("not_multipart: the body isn't multipart/form-data"),
+        );
+        responses.insert(
+            "422".into(),
+            error("crc_mismatch or parse_error: the replay could not be decoded"),
+        );
+        let mut busy = error("busy: every parse worker and queue slot is taken");
+        busy["headers"] = json!({
+            "Retry-After": {
+                "description": "Seconds to wait before retrying",
+                "schema": { "type": "integer" }
+            }
+        });
+        responses.insert("503".into(), busy);
+        for (status, response) in extra {
+            responses.insert(status.to_string(), response.clone());
+        }
+        json!({
+            "post": {
+                "summary": summary,
+                "requestBody": upload,
+                "responses": responses
+            }
+        })
+    };
+    let mut batch_route = upload_route(
+        "Ballchasing-style replays of every file in the form or in a zip archive",
+        ok("Result of each replay, keyed by file name", batch),
+        &[],
+    );
+    batch_route["post"]["requestBody"] = json!({
+        "required": true,
+        "content": {
+            "multipart/form-data": {
+                "schema": {
+                    "type": "object",
+                    "additionalProperties": {
+                        "type": "string",
+                        "format": "binary",
+                        "description": "A .replay file, or a .zip archive of .replay files"
+                    }
+                }
+            }
+        }
+    });
+    batch_route["post"]["responses"]["400"] = error(
+        "bad_multipart, empty_body or bad_archive: no usable replay in the form; \
+         errors of single replays are reported in the results instead",
+    );
+    batch_route["post"]["responses"]["413"] = error(
+        "file_too_large or too_many_files: the batch exceeds MAX_BATCH_UPLOAD_MB or \
+         MAX_BATCH_FILES; replays over MAX_UPLOAD_MB are reported in the results instead",
+    );
+    let mut jobs_route = upload_route(
+        "Queue a replay for parsing in the background",
+        json!({}),
+        &[(
+            "400",
+            error("bad_multipart, empty_body or invalid_callback: the form can't be queued"),
+        )],
+    );
+    let jobs_post = &mut jobs_route["post"];
+    jobs_post["requestBody"]["content"]["multipart/form-data"]["schema"]["properties"]
+        ["callback_url"] = json!({
+        "type": "string",
+        "format": "uri",
+        "description": "http(s) URL the finished job is POSTed to, in the GET /jobs/{id} format; \
+            the host must resolve to public addresses, or be listed in CALLBACK_HOSTS"
+    });
+    // Parsing happens later, so its errors end up in the job instead
+    if let Some(responses) = jobs_post["responses"].as_object_mut() {
+        for status in ["200", "422"] {
+            responses.remove(status);
+        }
+    }
+    jobs_post["responses"]["503"]["description"] =
+        json!("busy: MAX_QUEUED_JOBS jobs are already waiting");
+    jobs_post["responses"]["202"] = json!({
+        "description": "Job queued; poll the Location header",
+        "headers": { "Location": { "schema": { "type": "string" } } },
+        "content": { "application/json": { "schema": job } }
+    });
+    let admin_security = json!([{ "adminToken": [] }]);
+
+    json!({
+        "openapi": "3.0.3",
+        "info": {
+            "title": "RL-Dash replay parser",
+            "version": env!("CARGO_PKG_VERSION")
+        },
+        "paths": {
+            "/parse": upload_route(
+                "Ballchasing-style replay with stats",
+                ok("Parsed replay", replay),
+                &[],
+            ),
+            "/parse/batch": batch_route,
+            "/jobs": jobs_route,
+            "/jobs/{id}": {
+                "get": {
+                    "summary": "Status of a parse job, with the Ballchasing-style replay once done",
+                    "parameters": [{
+                        "name": "id",
+                        "in": "path",
+                        "required": true,
+                        "schema": { "type": "string", "format": "uuid" }
+                    }],
+                    "responses": {
+                        "200": ok("Job", job_report),
+                        "404": error("not_found: no job with this ID")
+                    }
+                }
+            },
+            "/camera": upload_route(
+                "Camera settings and ball cam usage of every player",
+                ok("One entry per player, blue team first", cameras),
+                &[],
+            ),
+            "/header": upload_route(
+                "Typed header properties",
+                ok("Header properties", properties),
+                &[(
+                    "422",
+                    error("crc_mismatch, parse_error, or invalid_header: a header property is missing or mistyped"),
+                )],
+            ),
+            "/v1/output": upload_route(
+                "Full replay in the versioned schema",
+                ok("Replay", root),
+                &[],
+            ),
+            "/output": upload_route(
+                "Raw boxcars replay, including network frames",
+                ok("Unversioned boxcars JSON; prefer /v1/output", json!({ "type": "object" })),
+                &[],
+            ),
+            "/output/basic": upload_route(
+                "Raw boxcars replay without network frames",
+                ok("Unversioned boxcars JSON; prefer /header", json!({ "type": "object" })),
+                &[],
+            ),
+            "/schema.ts": {
+                "get": {
+                    "summary": "TypeScript declarations of the /parse and /camera responses",
+                    "responses": {
+                        "200": {
+                            "description": "TypeScript source",
+                            "content": { "application/typescript": { "schema": { "type": "string" } } }
+                        }
+                    }
+                }
+            },
+            "/openapi.json": {
+                "get": {
+                    "summary": "This document",
+                    "responses": { "200": ok("OpenAPI document", json!({ "type": "object" })) }
+                }
+            },
+            "/schemas/{name}": {
+                "get": {
+                    "summary": "JSON Schema (draft 2020-12) of a response type",
+                    "parameters": [{
+                        "name": "name",
+                        "in": "path",
+                        "required": true,
+                        "schema": { "type": "string", "enum": JSON_SCHEMAS }
+                    }],
+                    "responses": {
+                        "200": ok("JSON Schema", json!({ "type": "object" })),
+                        "404": error("Unknown schema name")
+                    }
+                }
+            },
+            "/admin/catalog/reload": {
+                "post": {
+                    "summary": "Re-read the product catalog file",
+                    "security": admin_security,
+                    "responses": {
+                        "200": ok("Catalog reloaded", json!({
+                            "type": "object",
+                            "properties": {
+                                "path": { "type": "string" },
+                                "products": { "type": "integer" },
+                                "cache_purged": {
+                                    "type": "integer",
+                                    "description": "Cached parses dropped because they carry the old product names"
+                                }
+                            }
+                        })),
+                        "401": error("Invalid admin token, or ADMIN_TOKEN is not set"),
+                        "500": error("The catalog file could not be read")
+                    }
+                }
+            },
+            "/admin/catalog/unknown": {
+                "get": {
+                    "summary": "Product IDs seen in replays but missing from the catalog",
+                    "security": admin_security,
+                    "responses": {
+                        "200": ok("Unknown products by ID", unknown),
+                        "401": error("Invalid admin token, or ADMIN_TOKEN is not set")
+                    }
+                }
+            },
+            "/admin/cache": {
+                "get": {
+                    "summary": "Parse cache version, size and hit/miss counters",
+                    "security": admin_security,
+                    "responses": {
+                        "200": ok("Cache statistics", cache_stats),
+                        "401": error("Invalid admin token, or ADMIN_TOKEN is not set")
+                    }
+                }
+            },
+            "/admin/cache/purge": {
+                "post": {
+                    "summary": "Drop every cached parse, in memory and on disk",
+                    "security": admin_security,
+                    "responses": {
+                        "200": ok("Cache purged", json!({
+                            "type": "object",
+                            "properties": { "purged": { "type": "integer" } }
+                        })),
+                        "401": error("Invalid admin token, or ADMIN_TOKEN is not set")
+                    }
+                }
+            }
+        },
+        "components": {
+            "schemas": schemas,
+            "securitySchemes": {
+                "adminToken": {
+                    "type": "http",
+                    "scheme": "bearer",
+                    "description": "Required only when the server sets ADMIN_TOKEN"
+                }
+            }
+        }
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn typescript_matches_committed_file() {
+        let generated = typescript();
+
+        if std::env::var("UPDATE_SCHEMA").is_ok() {
+            std::fs::write(TYPESCRIPT_PATH, &generated).unwrap();
+            return;
+        }
+
+        let committed = std::fs::read_to_string(TYPESCRIPT_PATH).unwrap_or_default();
+        assert!(
+            committed == generated,
+            "{} is out of date; run `UPDATE_SCHEMA=1 cargo test` in server/ and commit the result",
+            TYPESCRIPT_PATH
+        );
+    }
+}
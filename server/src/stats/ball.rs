@@ -0,0 +1,38 @@
+use super::ActorTracker;
+use crate::types::BallStats;
+
+use boxcars::Frame;
+use std::collections::HashMap;
+
+/// Tracks which team has the ball and which half of the field it is in
+#[derive(Default)]
+pub struct BallCollector {
+    teams: [BallStats; 2],
+    players: HashMap<String, f32>,
+}
+
+impl BallCollector {
+    pub fn observe(&mut self, frame: &Frame, tracker: &ActorTracker) {
+        let Some(ball) = tracker.ball().and_then(|b| tracker.rigid_body(b)) else {
+            return;
+        };
+        let delta = frame.delta;
+
+        // Blue defends negative y
+        let side = if ball.location.y < 0.0 { 0 } else { 1 };
+        self.teams[side].time_in_side += delta;
+
+        if let Some(team) = tracker.possession() {
+            self.teams[usize::from(team)].possession_time += delta;
+        }
+
+        if let Some(player) = tracker.possession_player() {
+            *self.players.entry(player.to_string()).or_default() += delta;
+        }
+    }
+
+    /// Per-team ball stats and per-player possession time
+    pub fn finish(self) -> ([BallStats; 2], HashMap<String, f32>) {
+        (self.teams, self.players)
+    }
+}
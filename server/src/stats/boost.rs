@@ -0,0 +1,283 @@
+use super::{percent, ActorKind, ActorTracker, SUPERSONIC_SPEED};
+use crate::types::BoostStats;
+
+use boxcars::{ActorId, Attribute, Frame};
+use std::collections::HashMap;
+
+const BIG_PAD_AMOUNT: f32 = 100.0;
+const SMALL_PAD_AMOUNT: f32 = 12.0;
+
+/// Big pad centers (x, y) on standard soccar maps
+const BIG_PADS: [(f32, f32); 6] = [
+    (-3584.0, 0.0),
+    (3584.0, 0.0),
+    (-3072.0, 4096.0),
+    (3072.0, 4096.0),
+    (-3072.0, -4096.0),
+    (3072.0, -4096.0),
+];
+
+/// Cars picking up a pad are at most this far (uu) from its center
+const BIG_PAD_RADIUS: f32 = 400.0;
+
+#[derive(Default)]
+struct BoostTotals {
+    time: f32,
+    weighted_amount: f32,
+    used: f32,
+    used_supersonic: f32,
+    collected_big: f32,
+    collected_small: f32,
+    stolen_big: f32,
+    stolen_small: f32,
+    count_collected_big: u32,
+    count_collected_small: u32,
+    count_stolen_big: u32,
+    count_stolen_small: u32,
+    overfill: f32,
+    overfill_stolen: f32,
+    time_zero: f32,
+    time_full: f32,
+    time_bands: [f32; 4],
+}
+
+/// Tracks boost amounts and pad pickups per car
+#[derive(Default)]
+pub struct BoostCollector {
+    totals: HashMap<String, BoostTotals>,
+    /// Current boost (0-100) of each car
+    amounts: HashMap<ActorId, f32>,
+    /// Last seen pickup state of each pad; pickups are replicated more than once
+    pads: HashMap<ActorId, (Option<ActorId>, u8)>,
+}
+
+impl BoostCollector {
+    pub fn observe(&mut self, frame: &Frame, tracker: &ActorTracker) {
+        // Pickups first, so they see the boost the car had before this frame
+        for update in &frame.updated_actors {
+            let state = match &update.attribute {
+                Attribute::PickupNew(pickup) => (pickup.instigator, pickup.picked_up),
+                Attribute::Pickup(pickup) => (pickup.instigator, pickup.picked_up as u8),
+                _ => continue,
+            };
+
+            if self.pads.insert(update.actor_id, state) == Some(state) {
+                continue;
+            }
+
+            if let (Some(car), _) = state {
+                self.pickup(car, tracker);
+            }
+        }
+
+        for update in &frame.updated_actors {
+            let raw = match (tracker.attribute_name(update), &update.attribute) {
+                (_, Attribute::ReplicatedBoost(boost)) => boost.boost_amount,
+                ("TAGame.CarComponent_Boost_TA:ReplicatedBoostAmount", Attribute::Byte(b)) => *b,
+                _ => continue,
+            };
+
+            if tracker.kind(update.actor_id) != ActorKind::BoostComponent {
+                continue;
+            }
+
+            if let Some(car) = tracker.component_car(update.actor_id) {
+                self.set_amount(car, f32::from(raw) / 255.0 * 100.0, tracker);
+            }
+        }
+
+        for (&car, &amount) in &self.amounts {
+            let Some(player) = tracker.car_player(car) else {
+                continue;
+            };
+            let totals = self.totals.entry(player.to_string()).or_default();
+            let delta = frame.delta;
+
+            totals.time += delta;
+            totals.weighted_amount += amount * delta;
+
+            if amount <= 0.0 {
+                totals.time_zero += delta;
+            }
+            if amount >= 100.0 {
+                totals.time_full += delta;
+            }
+
+            let band = ((amount / 25.0) as usize).min(3);
+            totals.time_bands[band] += delta;
+        }
+
+        for actor in &frame.deleted_actors {
+            self.amounts.remove(actor);
+            self.pads.remove(actor);
+        }
+    }
+
+    pub fn finish(self) -> HashMap<String, BoostStats> {
+        self.totals
+            .into_iter()
+            .map(|(player, t)| (player, t.into_stats()))
+            .collect()
+    }
+
+    fn pickup(&mut self, car: ActorId, tracker: &ActorTracker) {
+        let Some(player) = tracker.car_player(car) else {
+            return;
+        };
+
+        let location = tracker.rigid_body(car).map(|rb| rb.location);
+        let big = location.is_some_and(|l| {
+            BIG_PADS
+                .iter()
+                .any(|(x, y)| (l.x - x).hypot(l.y - y) <= BIG_PAD_RADIUS)
+        });
+
+        // Blue defends negative y, so a blue player past midfield is stealing
+        let stolen = match (tracker.car_team(car), location) {
+            (Some(0), Some(l)) => l.y > 0.0,
+            (Some(1), Some(l)) => l.y < 0.0,
+            _ => false,
+        };
+
+        let pad = if big {
+            BIG_PAD_AMOUNT
+        } else {
+            SMALL_PAD_AMOUNT
+        };
+        let before = self.amounts.get(&car).copied().unwrap_or(0.0);
+        let gained = pad.min(100.0 - before).max(0.0);
+        let overfill = pad - gained;
+        self.amounts.insert(car, before + gained);
+
+        let t = self.totals.entry(player.to_string()).or_default();
+        if big {
+            t.collected_big += gained;
+            t.count_collected_big += 1;
+            if stolen {
+                t.stolen_big += gained;
+                t.count_stolen_big += 1;
+            }
+        } else {
+            t.collected_small += gained;
+            t.count_collected_small += 1;
+            if stolen {
+                t.stolen_small += gained;
+                t.count_stolen_small += 1;
+            }
+        }
+
+        t.overfill += overfill;
+        if stolen {
+            t.overfill_stolen += overfill;
+        }
+    }
+
+    fn set_amount(&mut self, car: ActorId, amount: f32, tracker: &ActorTracker) {
+        let previous = self.amounts.insert(car, amount);
+
+        let Some(used) = previous.map(|p| p - amount).filter(|used| *used > 0.0) else {
+            return;
+        };
+        let Some(player) = tracker.car_player(car) else {
+            return;
+        };
+
+        let t = self.totals.entry(player.to_string()).or_default();
+        t.used += used;
+        if tracker.speed(car) >= SUPERSONIC_SPEED {
+            t.used_supersonic += used;
+        }
+    }
+}
+
+impl BoostTotals {
+    fn into_stats(self) -> BoostStats {
+        let minutes = self.time / 60.0;
+        let per_minute = |amount: f32| if minutes > 0.0 { amount / minutes } else { 0.0 };
+        let collected = self.collected_big + self.collected_small;
+
+        BoostStats {
+            bpm: per_minute(self.used),
+            bcpm: per_minute(collected),
+            avg_amount: if self.time > 0.0 {
+                self.weighted_amount / self.time
+            } else {
+                0.0
+            },
+            amount_collected: collected.round() as u32,
+            amount_stolen: (self.stolen_big + self.stolen_small).round() as u32,
+            amount_collected_big: self.collected_big.round() as u32,
+            amount_stolen_big: self.stolen_big.round() as u32,
+            amount_collected_small: self.collected_small.round() as u32,
+            amount_stolen_small: self.stolen_small.round() as u32,
+            count_collected_big: self.count_collected_big,
+            count_stolen_big: self.count_stolen_big,
+            count_collected_small: self.count_collected_small,
+            count_stolen_small: self.count_stolen_small,
+            amount_overfill: self.overfill.round() as u32,
+            amount_overfill_stolen: self.overfill_stolen.round() as u32,
+            amount_used_while_supersonic: self.used_supersonic.round() as u32,
+            time_zero_boost: self.time_zero,
+            percent_zero_boost: percent(self.time_zero, self.time),
+            time_full_boost: self.time_full,
+            percent_full_boost: percent(self.time_full, self.time),
+            time_boost_0_25: self.time_bands[0],
+            time_boost_25_50: self.time_bands[1],
+            time_boost_50_75: self.time_bands[2],
+            time_boost_75_100: self.time_bands[3],
+            percent_boost_0_25: percent(self.time_bands[0], self.time),
+            percent_boost_25_50: percent(self.time_bands[1], self.time),
+            percent_boost_50_75: percent(self.time_bands[2], self.time),
+            percent_boost_75_100: percent(self.time_bands[3], self.time),
+        }
+    }
+}
+
+/// Team boost block; amounts are summed like Ballchasing does
+pub fn team_boost<'a>(players: impl IntoIterator<Item = &'a BoostStats>) -> BoostStats {
+    let mut team = BoostStats::default();
+    let mut count = 0.0;
+
+    for p in players {
+        count += 1.0;
+        team.bpm += p.bpm;
+        team.bcpm += p.bcpm;
+        team.avg_amount += p.avg_amount;
+        team.amount_collected += p.amount_collected;
+        team.amount_stolen += p.amount_stolen;
+        team.amount_collected_big += p.amount_collected_big;
+        team.amount_stolen_big += p.amount_stolen_big;
+        team.amount_collected_small += p.amount_collected_small;
+        team.amount_stolen_small += p.amount_stolen_small;
+        team.count_collected_big += p.count_collected_big;
+        team.count_stolen_big += p.count_stolen_big;
+        team.count_collected_small += p.count_collected_small;
+        team.count_stolen_small += p.count_stolen_small;
+        team.amount_overfill += p.amount_overfill;
+        team.amount_overfill_stolen += p.amount_overfill_stolen;
+        team.amount_used_while_supersonic += p.amount_used_while_supersonic;
+        team.time_zero_boost += p.time_zero_boost;
+        team.percent_zero_boost += p.percent_zero_boost;
+        team.time_full_boost += p.time_full_boost;
+        team.percent_full_boost += p.percent_full_boost;
+        team.time_boost_0_25 += p.time_boost_0_25;
+        team.time_boost_25_50 += p.time_boost_25_50;
+        team.time_boost_50_75 += p.time_boost_50_75;
+        team.time_boost_75_100 += p.time_boost_75_100;
+        team.percent_boost_0_25 += p.percent_boost_0_25;
+        team.percent_boost_25_50 += p.percent_boost_25_50;
+        team.percent_boost_50_75 += p.percent_boost_50_75;
+        team.percent_boost_75_100 += p.percent_boost_75_100;
+    }
+
+    if count > 0.0 {
+        team.percent_zero_boost /= count;
+        team.percent_full_boost /= count;
+        team.percent_boost_0_25 /= count;
+        team.percent_boost_25_50 /= count;
+        team.percent_boost_50_75 /= count;
+        team.percent_boost_75_100 /= count;
+    }
+
+    team
+}
@@ -0,0 +1,169 @@
+use super::{percent, ActorTracker};
+use crate::types::{CameraSettings, CameraStats};
+
+use boxcars::{ActorId, Attribute, CamSettings, Frame};
+use std::collections::HashMap;
+
+#[derive(Default)]
+struct CameraTotals {
+    time: f32,
+    time_ball_cam: f32,
+    time_behind_view: f32,
+    count_ball_cam_toggles: u32,
+}
+
+/// Camera state replicated on a PRI or on its camera settings actor
+#[derive(Default)]
+struct CameraState {
+    /// `None` until first replicated, so the initial value isn't counted as a toggle
+    ball_cam: Option<bool>,
+    behind_view: bool,
+}
+
+/// Reads each player's camera profile and tracks how they use ball cam
+#[derive(Default)]
+pub struct CameraCollector {
+    settings: HashMap<String, CamSettings>,
+    totals: HashMap<String, CameraTotals>,
+    /// Camera settings actor → the PRI it belongs to
+    camera_pris: HashMap<ActorId, ActorId>,
+    /// Latest camera state per PRI
+    states: HashMap<ActorId, CameraState>,
+    /// Profiles seen before their owner could be resolved
+    pending: HashMap<ActorId, CamSettings>,
+}
+
+impl CameraCollector {
+    pub fn observe(&mut self, frame: &Frame, tracker: &ActorTracker) {
+        for update in &frame.updated_actors {
+            let actor = update.actor_id;
+            match (tracker.attribute_name(update), &update.attribute) {
+                ("TAGame.CameraSettingsActor_TA:PRI", Attribute::ActiveActor(link)) => {
+                    self.camera_pris.insert(actor, link.actor);
+                }
+                (
+                    "TAGame.CameraSettingsActor_TA:ProfileSettings"
+                    | "TAGame.PRI_TA:CameraSettings",
+                    Attribute::CamSettings(settings),
+                ) => {
+                    self.pending.insert(actor, **settings);
+                }
+                (
+                    "TAGame.CameraSettingsActor_TA:bUsingSecondaryCamera"
+                    | "TAGame.PRI_TA:bUsingSecondaryCamera",
+                    Attribute::Boolean(on),
+                ) => {
+                    let pri = self.pri(actor);
+                    let state = self.states.entry(pri).or_default();
+                    let toggled = state.ball_cam.is_some_and(|was_on| was_on != *on);
+                    state.ball_cam = Some(*on);
+                    if toggled {
+                        if let Some(player) = tracker.pri_player(pri) {
+                            self.entry(player).count_ball_cam_toggles += 1;
+                        }
+                    }
+                }
+                (
+                    "TAGame.CameraSettingsActor_TA:bUsingBehindView"
+                    | "TAGame.PRI_TA:bUsingBehindView",
+                    Attribute::Boolean(on),
+                ) => {
+                    let pri = self.pri(actor);
+                    self.states.entry(pri).or_default().behind_view = *on;
+                }
+                _ => {}
+            }
+        }
+
+        // Profiles can arrive before the camera actor is linked to its PRI
+        let resolved: Vec<(ActorId, String)> = self
+            .pending
+            .keys()
+            .filter_map(|actor| Some((*actor, tracker.pri_player(self.pri(*actor))?.to_string())))
+            .collect();
+        for (actor, player) in resolved {
+            if let Some(settings) = self.pending.remove(&actor) {
+                self.settings.insert(player, settings);
+            }
+        }
+
+        let delta = frame.delta;
+        let players: Vec<(String, bool, bool)> = self
+            .states
+            .iter()
+            .filter_map(|(pri, state)| {
+                let player = tracker.pri_player(*pri)?;
+                let ball_cam = state.ball_cam.unwrap_or(false);
+                Some((player.to_string(), ball_cam, state.behind_view))
+            })
+            .collect();
+        for (player, ball_cam, behind_view) in players {
+            let t = self.entry(&player);
+            t.time += delta;
+            if ball_cam {
+                t.time_ball_cam += delta;
+            }
+            if behind_view {
+                t.time_behind_view += delta;
+            }
+        }
+
+        for actor in &frame.deleted_actors {
+            self.camera_pris.remove(actor);
+            self.states.remove(actor);
+        }
+    }
+
+    /// Camera profile and usage per player key
+    pub fn finish(
+        self,
+    ) -> (
+        HashMap<String, CameraSettings>,
+        HashMap<String, CameraStats>,
+    ) {
+        let settings = self
+            .settings
+            .into_iter()
+            .map(|(player, s)| {
+                (
+                    player,
+                    CameraSettings {
+                        fov: s.fov,
+                        height: s.height,
+                        pitch: s.angle,
+                        distance: s.distance,
+                        stiffness: s.stiffness,
+                        swivel_speed: s.swivel,
+                        transition_speed: s.transition,
+                    },
+                )
+            })
+            .collect();
+
+        let stats = self
+            .totals
+            .into_iter()
+            .map(|(player, t)| {
+                let stats = CameraStats {
+                    time_ball_cam: t.time_ball_cam,
+                    percent_ball_cam: percent(t.time_ball_cam, t.time),
+                    count_ball_cam_toggles: t.count_ball_cam_toggles,
+                    time_behind_view: t.time_behind_view,
+                    percent_behind_view: percent(t.time_behind_view, t.time),
+                };
+                (player, stats)
+            })
+            .collect();
+
+        (settings, stats)
+    }
+
+    /// PRI an actor's camera state belongs to; PRIs own their state directly
+    fn pri(&self, actor: ActorId) -> ActorId {
+        self.camera_pris.get(&actor).copied().unwrap_or(actor)
+    }
+
+    fn entry(&mut self, player: &str) -> &mut CameraTotals {
+        self.totals.entry(player.to_string()).or_default()
+    }
+}
@@ -0,0 +1,58 @@
+use super::ActorTracker;
+use crate::types::DemoStats;
+
+use boxcars::{ActorId, Attribute, Frame};
+use std::collections::{HashMap, HashSet};
+
+/// Counts demolitions inflicted and taken per player
+#[derive(Default)]
+pub struct DemoCollector {
+    stats: HashMap<String, DemoStats>,
+    /// Victim cars already credited; the demolish attribute is replicated
+    /// repeatedly until the wrecked car actor is destroyed
+    demolished: HashSet<ActorId>,
+}
+
+impl DemoCollector {
+    pub fn observe(&mut self, frame: &Frame, tracker: &ActorTracker) {
+        for update in &frame.updated_actors {
+            let (attacker_pri, attacker_car, victim_car) = match &update.attribute {
+                Attribute::Demolish(demo) => (None, demo.attacker, demo.victim),
+                Attribute::DemolishFx(demo) => (None, demo.attacker, demo.victim),
+                Attribute::DemolishExtended(demo) => {
+                    let pri = demo.attacker_pri.active.then_some(demo.attacker_pri.actor);
+                    (pri, demo.attacker.actor, demo.victim.actor)
+                }
+                _ => continue,
+            };
+
+            if !self.demolished.insert(victim_car) {
+                continue;
+            }
+
+            let attacker = attacker_pri
+                .and_then(|pri| tracker.pri_player(pri))
+                .or_else(|| tracker.car_player(attacker_car));
+
+            if let Some(player) = attacker {
+                self.entry(player).inflicted += 1;
+            }
+
+            if let Some(player) = tracker.car_player(victim_car) {
+                self.entry(player).taken += 1;
+            }
+        }
+
+        for actor in &frame.deleted_actors {
+            self.demolished.remove(actor);
+        }
+    }
+
+    pub fn finish(self) -> HashMap<String, DemoStats> {
+        self.stats
+    }
+
+    fn entry(&mut self, player: &str) -> &mut DemoStats {
+        self.stats.entry(player.to_string()).or_default()
+    }
+}
@@ -0,0 +1,56 @@
+use super::ActorTracker;
+
+use boxcars::{Attribute, Frame};
+
+/// Replicated match settings that tell playlists and game modes apart
+#[derive(Debug, Default)]
+pub struct GameModeInfo {
+    /// Playlist ID from `ProjectX.GRI_X:ReplicatedGamePlaylist`
+    pub playlist: Option<i32>,
+    /// Game mode index from `TAGame.GameEvent_TA:GameMode`
+    pub game_mode: Option<u8>,
+    /// Archetype of the first ball, e.g. `Archetypes.Ball.Ball_Puck`
+    pub ball: Option<String>,
+    /// Rumble power-ups were handed out
+    pub rumble: bool,
+    /// Private match settings were replicated
+    pub private_match: bool,
+}
+
+/// Reads the playlist and game mode attributes and the archetypes that give a mode away
+#[derive(Default)]
+pub struct GameModeCollector {
+    info: GameModeInfo,
+}
+
+impl GameModeCollector {
+    pub fn observe(&mut self, frame: &Frame, tracker: &ActorTracker) {
+        for actor in &frame.new_actors {
+            let name = tracker.object_name(actor.object_id.0);
+            if name.starts_with("Archetypes.Ball.") && self.info.ball.is_none() {
+                self.info.ball = Some(name.to_string());
+            } else if name.starts_with("Archetypes.SpecialPickups.") {
+                self.info.rumble = true;
+            }
+        }
+
+        for update in &frame.updated_actors {
+            match (tracker.attribute_name(update), &update.attribute) {
+                ("ProjectX.GRI_X:ReplicatedGamePlaylist", Attribute::Int(playlist))
+                    if *playlist > 0 =>
+                {
+                    self.info.playlist = Some(*playlist);
+                }
+                ("TAGame.GameEvent_TA:GameMode", Attribute::GameMode(_, mode)) => {
+                    self.info.game_mode = Some(*mode);
+                }
+                (_, Attribute::PrivateMatch(_)) => self.info.private_match = true,
+                _ => {}
+            }
+        }
+    }
+
+    pub fn finish(self) -> GameModeInfo {
+        self.info
+    }
+}
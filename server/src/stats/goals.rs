@@ -0,0 +1,146 @@
+use super::ActorTracker;
+use crate::types::{Goal, GoalEvent, Location, Scoreline};
+
+use boxcars::{ActorId, Attribute, Frame, RigidBody};
+use std::collections::HashMap;
+
+/// uu/s to km/h, the unit the game shows for goal speed
+const UU_PER_SECOND_TO_KPH: f32 = 0.036;
+
+/// An assist is replicated shortly after the goal it belongs to (seconds)
+const ASSIST_WINDOW: (f32, f32) = (-1.0, 5.0);
+
+/// Game state captured on the frame a goal was scored
+struct GoalSnapshot {
+    time: f32,
+    seconds_remaining: Option<i32>,
+    overtime: bool,
+    ball: Option<RigidBody>,
+}
+
+/// An assist counter going up on a player's PRI
+struct AssistEvent {
+    time: f32,
+    name: String,
+    team: Option<u8>,
+}
+
+/// Joins the header goal list with the network frames to build a goal timeline
+pub struct GoalCollector<'a> {
+    goals: &'a [Goal],
+    frame_index: usize,
+    seconds_remaining: Option<i32>,
+    overtime: bool,
+    assist_counts: HashMap<ActorId, i32>,
+    assists: Vec<AssistEvent>,
+    snapshots: HashMap<u32, GoalSnapshot>,
+}
+
+impl<'a> GoalCollector<'a> {
+    pub fn new(goals: &'a [Goal]) -> Self {
+        Self {
+            goals,
+            frame_index: 0,
+            seconds_remaining: None,
+            overtime: false,
+            assist_counts: HashMap::new(),
+            assists: Vec::new(),
+            snapshots: HashMap::new(),
+        }
+    }
+
+    pub fn observe(&mut self, frame: &Frame, tracker: &ActorTracker) {
+        let index = self.frame_index as u32;
+        self.frame_index += 1;
+
+        for update in &frame.updated_actors {
+            match (tracker.attribute_name(update), &update.attribute) {
+                ("TAGame.GameEvent_Soccar_TA:SecondsRemaining", Attribute::Int(seconds)) => {
+                    self.seconds_remaining = Some(*seconds);
+                }
+                ("TAGame.GameEvent_Soccar_TA:bOverTime", Attribute::Boolean(overtime)) => {
+                    self.overtime = *overtime;
+                }
+                ("TAGame.PRI_TA:MatchAssists", Attribute::Int(count)) => {
+                    let previous = self.assist_counts.insert(update.actor_id, *count);
+                    if *count <= previous.unwrap_or(0) {
+                        continue;
+                    }
+                    if let Some(name) = tracker.pri_name(update.actor_id) {
+                        self.assists.push(AssistEvent {
+                            time: frame.time,
+                            name: name.to_string(),
+                            team: tracker.pri_team(update.actor_id),
+                        });
+                    }
+                }
+                _ => {}
+            }
+        }
+
+        if self.goals.iter().any(|g| g.frame == index) {
+            self.snapshots.insert(
+                index,
+                GoalSnapshot {
+                    time: frame.time,
+                    seconds_remaining: self.seconds_remaining,
+                    overtime: self.overtime,
+                    ball: tracker.ball().and_then(|b| tracker.rigid_body(b)).copied(),
+                },
+            );
+        }
+    }
+
+    pub fn finish(self) -> Vec<GoalEvent> {
+        let mut score = Scoreline::default();
+        let mut used_assists = vec![false; self.assists.len()];
+
+        self.goals
+            .iter()
+            .map(|goal| {
+                let blue = goal.player_team == 0;
+                if blue {
+                    score.blue += 1;
+                } else {
+                    score.orange += 1;
+                }
+
+                let snapshot = self.snapshots.get(&goal.frame);
+                let time = snapshot.map(|s| s.time);
+                let ball = snapshot.and_then(|s| s.ball);
+
+                let assister = time.and_then(|time| {
+                    let (i, assist) = self.assists.iter().enumerate().find(|(i, a)| {
+                        let offset = a.time - time;
+                        !used_assists[*i]
+                            && (ASSIST_WINDOW.0..=ASSIST_WINDOW.1).contains(&offset)
+                            && a.team == Some(goal.player_team)
+                            && a.name != goal.player_name
+                    })?;
+                    used_assists[i] = true;
+                    Some(assist.name.clone())
+                });
+
+                GoalEvent {
+                    frame: goal.frame,
+                    time: time.unwrap_or_default(),
+                    seconds_remaining: snapshot.and_then(|s| s.seconds_remaining),
+                    overtime: snapshot.is_some_and(|s| s.overtime),
+                    team: if blue { "blue" } else { "orange" }.into(),
+                    scorer: goal.player_name.clone(),
+                    assister,
+                    score: score.clone(),
+                    ball_speed: ball
+                        .and_then(|b| b.linear_velocity)
+                        .map(|v| (v.x * v.x + v.y * v.y + v.z * v.z).sqrt() * UU_PER_SECOND_TO_KPH)
+                        .unwrap_or_default(),
+                    ball_location: ball.map(|b| Location {
+                        x: b.location.x,
+                        y: b.location.y,
+                        z: b.location.z,
+                    }),
+                }
+            })
+            .collect()
+    }
+}
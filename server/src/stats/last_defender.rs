@@ -0,0 +1,94 @@
+use super::{attacking_y, ActorKind, ActorTracker};
+
+use crate::types::Goal;
+
+use boxcars::{Attribute, Frame};
+use std::collections::HashMap;
+
+/// Explosions closer together than this (seconds) belong to the same goal
+const EXPLOSION_DEDUPE_SECONDS: f32 = 3.0;
+
+/// Credits each goal against to the defender nearest their own goal line
+pub struct LastDefenderCollector {
+    counts: HashMap<String, u32>,
+    /// Frame number and scoring team of every goal listed in the header
+    header_goals: Vec<(usize, u8)>,
+    frame_index: usize,
+    last_explosion: Option<f32>,
+}
+
+impl LastDefenderCollector {
+    pub fn new(goals: &[Goal]) -> Self {
+        Self {
+            counts: HashMap::new(),
+            header_goals: goals
+                .iter()
+                .map(|g| (g.frame as usize, g.player_team))
+                .collect(),
+            frame_index: 0,
+            last_explosion: None,
+        }
+    }
+
+    pub fn observe(&mut self, frame: &Frame, tracker: &ActorTracker) {
+        let index = self.frame_index;
+        self.frame_index += 1;
+
+        if !self.header_goals.is_empty() {
+            let scorers: Vec<u8> = self
+                .header_goals
+                .iter()
+                .filter(|(goal_frame, _)| *goal_frame == index)
+                .map(|(_, team)| *team)
+                .collect();
+            for team in scorers {
+                self.credit(1 - team, tracker);
+            }
+            return;
+        }
+
+        // Older or trimmed replays have no goal list; fall back to the ball exploding
+        for update in &frame.updated_actors {
+            let location = match &update.attribute {
+                Attribute::Explosion(e) => e.location,
+                Attribute::ExtendedExplosion(e) => e.explosion.location,
+                _ => continue,
+            };
+            if tracker.kind(update.actor_id) != ActorKind::Ball {
+                continue;
+            }
+
+            let recent = self
+                .last_explosion
+                .is_some_and(|t| frame.time - t < EXPLOSION_DEDUPE_SECONDS);
+            self.last_explosion = Some(frame.time);
+            if recent {
+                continue;
+            }
+
+            // The ball explodes in the goal of the team that conceded
+            let defending = if location.y > 0.0 { 1 } else { 0 };
+            self.credit(defending, tracker);
+        }
+    }
+
+    pub fn finish(self) -> HashMap<String, u32> {
+        self.counts
+    }
+
+    fn credit(&mut self, defending: u8, tracker: &ActorTracker) {
+        let last_defender = tracker
+            .cars()
+            .filter(|car| tracker.car_team(*car) == Some(defending))
+            .filter_map(|car| {
+                let player = tracker.car_player(car)?;
+                let y = attacking_y(defending, tracker.rigid_body(car)?.location.y);
+                Some((player, y))
+            })
+            .min_by(|a, b| a.1.total_cmp(&b.1));
+
+        if let Some((player, _)) = last_defender {
+            *self.counts.entry(player.to_string()).or_default() += 1;
+        }
+    }
+}
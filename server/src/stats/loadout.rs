@@ -0,0 +1,233 @@
+use super::ActorTracker;
+use crate::catalog::Catalog;
+use crate::types::paints::PAINTS;
+use crate::types::{CarPaint, LoadoutItem, PlayerLoadout, ProductAttribute};
+
+use boxcars::{ActorId, Attribute, Frame, Loadout, Product, ProductValue, TeamPaint};
+use std::collections::HashMap;
+
+/// Slots of `ClientLoadoutsOnline`, in the order the game replicates them
+const SLOT_BODY: usize = 0;
+const SLOT_DECAL: usize = 1;
+const SLOT_WHEELS: usize = 2;
+const SLOT_BOOST: usize = 3;
+const SLOT_ANTENNA: usize = 4;
+const SLOT_TOPPER: usize = 5;
+const SLOT_ENGINE_AUDIO: usize = 12;
+const SLOT_TRAIL: usize = 13;
+const SLOT_GOAL_EXPLOSION: usize = 14;
+const SLOT_BANNER: usize = 15;
+
+/// Product attributes per loadout slot
+type SlotAttributes = Vec<Vec<ProductAttribute>>;
+
+/// Everything a player equipped for the team they played on
+#[derive(Debug, Clone)]
+pub struct Cosmetics {
+    pub loadout: Loadout,
+    pub attributes: SlotAttributes,
+    pub paint: Option<TeamPaint>,
+}
+
+/// Loadouts of both teams as replicated on a PRI
+#[derive(Default)]
+struct TeamCosmetics {
+    loadouts: Option<(Loadout, Loadout)>,
+    attributes: Option<(SlotAttributes, SlotAttributes)>,
+}
+
+/// Collects the loadouts, product attributes and paint replicated for each
+/// player and keeps the ones matching their team
+#[derive(Default)]
+pub struct LoadoutCollector {
+    resolved: HashMap<String, Cosmetics>,
+    /// Latest cosmetics per PRI, resolved once the PRI goes away
+    pending: HashMap<ActorId, TeamCosmetics>,
+    /// Latest paint per car, resolved once the car goes away
+    paints: HashMap<ActorId, TeamPaint>,
+    resolved_paints: HashMap<String, TeamPaint>,
+}
+
+impl LoadoutCollector {
+    pub fn observe(&mut self, frame: &Frame, tracker: &ActorTracker) {
+        for update in &frame.updated_actors {
+            match (tracker.attribute_name(update), &update.attribute) {
+                ("TAGame.PRI_TA:ClientLoadouts", Attribute::TeamLoadout(loadouts)) => {
+                    self.entry(update.actor_id).loadouts = Some((loadouts.blue, loadouts.orange));
+                }
+                // Older replays replicate a single loadout for both teams
+                ("TAGame.PRI_TA:ClientLoadout", Attribute::Loadout(loadout)) => {
+                    self.entry(update.actor_id).loadouts = Some((**loadout, **loadout));
+                }
+                ("TAGame.PRI_TA:ClientLoadoutsOnline", Attribute::LoadoutsOnline(online)) => {
+                    let blue = slot_attributes(&online.blue, tracker);
+                    let orange = slot_attributes(&online.orange, tracker);
+                    self.entry(update.actor_id).attributes = Some((blue, orange));
+                }
+                ("TAGame.PRI_TA:ClientLoadoutOnline", Attribute::LoadoutOnline(online)) => {
+                    let attributes = slot_attributes(online, tracker);
+                    self.entry(update.actor_id).attributes = Some((attributes.clone(), attributes));
+                }
+                ("TAGame.Car_TA:TeamPaint", Attribute::TeamPaint(paint)) => {
+                    self.paints.insert(update.actor_id, *paint);
+                }
+                _ => {}
+            }
+        }
+
+        for actor in &frame.deleted_actors {
+            self.resolve(*actor, tracker);
+        }
+    }
+
+    /// Cosmetics per player key
+    pub fn finish(mut self, tracker: &ActorTracker) -> HashMap<String, Cosmetics> {
+        let actors: Vec<ActorId> = self
+            .pending
+            .keys()
+            .chain(self.paints.keys())
+            .copied()
+            .collect();
+        for actor in actors {
+            self.resolve(actor, tracker);
+        }
+
+        for (player, paint) in self.resolved_paints {
+            if let Some(cosmetics) = self.resolved.get_mut(&player) {
+                cosmetics.paint = Some(paint);
+            }
+        }
+        self.resolved
+    }
+
+    fn entry(&mut self, pri: ActorId) -> &mut TeamCosmetics {
+        self.pending.entry(pri).or_default()
+    }
+
+    /// Attach whatever was pending on a PRI or car actor to its player
+    fn resolve(&mut self, actor: ActorId, tracker: &ActorTracker) {
+        if let Some(paint) = self.paints.remove(&actor) {
+            if let Some(player) = tracker.car_player(actor) {
+                self.resolved_paints.insert(player.to_string(), paint);
+            }
+        }
+
+        let Some(pending) = self.pending.remove(&actor) else {
+            return;
+        };
+        let (Some(player), Some(loadouts)) = (tracker.pri_player(actor), pending.loadouts) else {
+            return;
+        };
+
+        let team = tracker.pri_team(actor);
+        self.resolved.insert(
+            player.to_string(),
+            Cosmetics {
+                loadout: for_team(loadouts, team),
+                attributes: pending
+                    .attributes
+                    .map(|a| for_team(a, team))
+                    .unwrap_or_default(),
+                paint: None,
+            },
+        );
+    }
+}
+
+/// Pick the blue or orange side of a team loadout
+fn for_team<T>((blue, orange): (T, T), team: Option<u8>) -> T {
+    if team == Some(1) {
+        orange
+    } else {
+        blue
+    }
+}
+
+fn slot_attributes(slots: &[Vec<Product>], tracker: &ActorTracker) -> SlotAttributes {
+    slots
+        .iter()
+        .map(|products| {
+            products
+                .iter()
+                .filter_map(|p| product_attribute(p, tracker))
+                .collect()
+        })
+        .collect()
+}
+
+fn product_attribute(product: &Product, tracker: &ActorTracker) -> Option<ProductAttribute> {
+    let (kind, id, name) = match &product.value {
+        ProductValue::NoColor => return None,
+        ProductValue::OldPaint(id) | ProductValue::NewPaint(id) => ("painted", Some(*id), None),
+        ProductValue::OldColor(id) | ProductValue::NewColor(id) => ("color", Some(*id), None),
+        ProductValue::SpecialEdition(id) => ("special_edition", Some(*id), None),
+        ProductValue::OldTeamEdition(id) | ProductValue::NewTeamEdition(id) => {
+            ("team_edition", Some(*id), None)
+        }
+        ProductValue::Title(title) => ("title", None, Some(title.clone())),
+        // Attributes such as certifications are listed without a value
+        ProductValue::Absent => match tracker.object_name(product.object_ind.0) {
+            "TAGame.ProductAttribute_Certified_TA" => ("certified", None, None),
+            class => (class, None, None),
+        },
+    };
+
+    Some(ProductAttribute {
+        kind: kind.to_string(),
+        id,
+        name,
+    })
+}
+
+/// Loadout block of a player, with names filled in from the product catalog
+pub fn player_loadout(cosmetics: &Cosmetics, catalog: &Catalog) -> PlayerLoadout {
+    let item = |slot: usize, id: Option<u32>| {
+        let id = id.filter(|id| *id != 0)?;
+        let attributes = cosmetics
+            .attributes
+            .get(slot)
+            .cloned()
+            .unwrap_or_default()
+            .into_iter()
+            .map(|mut attribute| {
+                if attribute.kind == "painted" {
+                    attribute.name = attribute
+                        .id
+                        .and_then(|id| PAINTS.get(&id))
+                        .map(|name| name.to_string());
+                }
+                attribute
+            })
+            .collect();
+        Some(LoadoutItem {
+            id,
+            name: catalog.name(id).map(str::to_string),
+            attributes,
+        })
+    };
+    let finish = |id: u32| LoadoutItem {
+        id,
+        name: catalog.name(id).map(str::to_string),
+        attributes: vec![],
+    };
+
+    let l = &cosmetics.loadout;
+    PlayerLoadout {
+        body: item(SLOT_BODY, Some(l.body)),
+        decal: item(SLOT_DECAL, Some(l.decal)),
+        wheels: item(SLOT_WHEELS, Some(l.wheels)),
+        boost: item(SLOT_BOOST, Some(l.rocket_trail)),
+        antenna: item(SLOT_ANTENNA, Some(l.antenna)),
+        topper: item(SLOT_TOPPER, Some(l.topper)),
+        engine_audio: item(SLOT_ENGINE_AUDIO, l.engine_audio),
+        trail: item(SLOT_TRAIL, l.trail),
+        goal_explosion: item(SLOT_GOAL_EXPLOSION, l.goal_explosion),
+        banner: item(SLOT_BANNER, l.banner),
+        paint: cosmetics.paint.map(|paint| CarPaint {
+            primary_color: paint.primary_color,
+            accent_color: paint.accent_color,
+            primary_finish: finish(paint.primary_finish),
+            accent_finish: finish(paint.accent_finish),
+        }),
+    }
+}
@@ -0,0 +1,406 @@
+mod ball;
+mod boost;
+mod camera;
+mod demos;
+mod game_mode;
+mod goals;
+mod last_defender;
+mod loadout;
+mod movement;
+mod positioning;
+
+pub use ball::BallCollector;
+// The `team_*` blocks sum the players' totals, and average their averages and percentages
+pub use boost::{team_boost, BoostCollector};
+pub use camera::CameraCollector;
+pub use demos::DemoCollector;
+pub use game_mode::{GameModeCollector, GameModeInfo};
+pub use goals::GoalCollector;
+pub use last_defender::LastDefenderCollector;
+pub use loadout::{player_loadout, Cosmetics, LoadoutCollector};
+pub use movement::{team_movement, MovementCollector};
+pub use positioning::{team_positioning, PositioningCollector};
+
+use crate::helpers::get_goals;
+use crate::types::{
+    BallStats, BoostStats, CameraSettings, CameraStats, DemoStats, GoalEvent, MovementStats,
+    PositioningStats,
+};
+
+use boxcars::{ActorId, Attribute, Frame, RemoteId, Replay, RigidBody, UpdatedAttribute, Vector3f};
+use std::collections::HashMap;
+
+/// Bump whenever a collector's output changes, so cached parses are recomputed
+pub const STATS_VERSION: u32 = 6;
+
+/// Cars at or above this speed (uu/s) are supersonic
+pub const SUPERSONIC_SPEED: f32 = 2200.0;
+
+/// A car whose center is this close (uu) to the ball's is treated as touching it
+const TOUCH_DISTANCE: f32 = 250.0;
+
+/// What kind of object an actor was spawned from
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ActorKind {
+    Car,
+    Ball,
+    Pri,
+    BoostComponent,
+    Team(u8),
+    Other,
+}
+
+/// Keeps track of which actors are alive and how they link to each other
+/// while walking the network frames.
+pub struct ActorTracker<'a> {
+    objects: &'a [String],
+    kinds: HashMap<ActorId, ActorKind>,
+    car_pris: HashMap<ActorId, ActorId>,
+    component_cars: HashMap<ActorId, ActorId>,
+    pri_names: HashMap<ActorId, String>,
+    pri_ids: HashMap<ActorId, String>,
+    pri_teams: HashMap<ActorId, ActorId>,
+    rigid_bodies: HashMap<ActorId, RigidBody>,
+    ball_hit_team: Option<u8>,
+    /// Player and team of the last car seen touching the ball
+    last_touch: Option<(String, u8)>,
+}
+
+impl<'a> ActorTracker<'a> {
+    pub fn new(objects: &'a [String]) -> Self {
+        Self {
+            objects,
+            kinds: HashMap::new(),
+            car_pris: HashMap::new(),
+            component_cars: HashMap::new(),
+            pri_names: HashMap::new(),
+            pri_ids: HashMap::new(),
+            pri_teams: HashMap::new(),
+            rigid_bodies: HashMap::new(),
+            ball_hit_team: None,
+            last_touch: None,
+        }
+    }
+
+    /// Name of the object an attribute update refers to (e.g. `TAGame.Car_TA:ReplicatedDemolish`)
+    pub fn attribute_name(&self, update: &UpdatedAttribute) -> &str {
+        self.object_name(update.object_id.0)
+    }
+
+    pub fn kind(&self, actor: ActorId) -> ActorKind {
+        self.kinds.get(&actor).copied().unwrap_or(ActorKind::Other)
+    }
+
+    /// Every car actor currently alive
+    pub fn cars(&self) -> impl Iterator<Item = ActorId> + '_ {
+        self.kinds
+            .iter()
+            .filter(|(_, kind)| **kind == ActorKind::Car)
+            .map(|(actor, _)| *actor)
+    }
+
+    /// The ball actor currently alive, if any
+    pub fn ball(&self) -> Option<ActorId> {
+        self.kinds
+            .iter()
+            .find(|(_, kind)| **kind == ActorKind::Ball)
+            .map(|(actor, _)| *actor)
+    }
+
+    /// Team that last touched the ball; replays that don't replicate the
+    /// ball's hit team fall back to the nearest car
+    pub fn possession(&self) -> Option<u8> {
+        self.ball_hit_team
+            .or_else(|| self.last_touch.as_ref().map(|(_, team)| *team))
+    }
+
+    /// Player of the team in possession who last touched the ball
+    pub fn possession_player(&self) -> Option<&str> {
+        let (name, team) = self.last_touch.as_ref()?;
+        (self.possession() == Some(*team)).then_some(name.as_str())
+    }
+
+    /// PRI actor of the player driving a car
+    pub fn car_pri(&self, car: ActorId) -> Option<ActorId> {
+        self.car_pris.get(&car).copied()
+    }
+
+    /// Car a component (boost, jump, dodge, ...) is attached to
+    pub fn component_car(&self, component: ActorId) -> Option<ActorId> {
+        self.component_cars.get(&component).copied()
+    }
+
+    pub fn pri_name(&self, pri: ActorId) -> Option<&str> {
+        self.pri_names.get(&pri).map(String::as_str)
+    }
+
+    /// Player key of a PRI: its platform ID, or its name when it has none (see `player_key`)
+    pub fn pri_player(&self, pri: ActorId) -> Option<&str> {
+        self.pri_ids
+            .get(&pri)
+            .or_else(|| self.pri_names.get(&pri))
+            .map(String::as_str)
+    }
+
+    /// Team index (0 = blue, 1 = orange) of a PRI
+    pub fn pri_team(&self, pri: ActorId) -> Option<u8> {
+        match self.kind(*self.pri_teams.get(&pri)?) {
+            ActorKind::Team(team) => Some(team),
+            _ => None,
+        }
+    }
+
+    /// Player key for a car actor, resolved through its PRI
+    pub fn car_player(&self, car: ActorId) -> Option<&str> {
+        self.car_pri(car).and_then(|pri| self.pri_player(pri))
+    }
+
+    pub fn car_team(&self, car: ActorId) -> Option<u8> {
+        self.car_pri(car).and_then(|pri| self.pri_team(pri))
+    }
+
+    /// Last replicated physics state of an actor
+    pub fn rigid_body(&self, actor: ActorId) -> Option<&RigidBody> {
+        self.rigid_bodies.get(&actor)
+    }
+
+    /// Speed (uu/s) of an actor, zero while it is asleep
+    pub fn speed(&self, actor: ActorId) -> f32 {
+        self.rigid_body(actor)
+            .and_then(|rb| rb.linear_velocity)
+            .map(|v| (v.x * v.x + v.y * v.y + v.z * v.z).sqrt())
+            .unwrap_or(0.0)
+    }
+
+    /// Register new actors and apply the attribute updates of a frame
+    fn apply_updates(&mut self, frame: &Frame) {
+        for actor in &frame.new_actors {
+            let kind = classify(self.object_name(actor.object_id.0));
+            self.kinds.insert(actor.actor_id, kind);
+        }
+
+        for update in &frame.updated_actors {
+            match (self.attribute_name(update), &update.attribute) {
+                // The link is cleared when a car is demolished; keep the last
+                // known PRI so the wreck can still be attributed
+                ("Engine.Pawn:PlayerReplicationInfo", Attribute::ActiveActor(link))
+                    if link.active =>
+                {
+                    self.car_pris.insert(update.actor_id, link.actor);
+                }
+                ("TAGame.CarComponent_TA:Vehicle", Attribute::ActiveActor(link)) if link.active => {
+                    self.component_cars.insert(update.actor_id, link.actor);
+                }
+                ("Engine.PlayerReplicationInfo:PlayerName", Attribute::String(name)) => {
+                    self.pri_names.insert(update.actor_id, name.clone());
+                }
+                ("Engine.PlayerReplicationInfo:UniqueId", Attribute::UniqueId(id)) => {
+                    if let Some(id) = remote_id(&id.remote_id) {
+                        self.pri_ids.insert(update.actor_id, id);
+                    }
+                }
+                ("Engine.PlayerReplicationInfo:Team", Attribute::ActiveActor(link)) => {
+                    self.pri_teams.insert(update.actor_id, link.actor);
+                }
+                ("TAGame.Ball_TA:HitTeamNum", Attribute::Byte(team)) if *team <= 1 => {
+                    self.ball_hit_team = Some(*team);
+                }
+                (_, Attribute::RigidBody(rb)) => {
+                    self.rigid_bodies.insert(update.actor_id, *rb);
+                }
+                _ => {}
+            }
+        }
+
+        self.update_last_touch();
+    }
+
+    fn update_last_touch(&mut self) {
+        let Some(ball) = self.ball().and_then(|b| self.rigid_body(b)) else {
+            return;
+        };
+        let ball = ball.location;
+
+        let toucher = self
+            .cars()
+            .filter_map(|car| {
+                let d = distance(&self.rigid_body(car)?.location, &ball);
+                (d <= TOUCH_DISTANCE).then_some((car, d))
+            })
+            .min_by(|a, b| a.1.total_cmp(&b.1))
+            .and_then(|(car, _)| Some((self.car_player(car)?.to_string(), self.car_team(car)?)));
+
+        if toucher.is_some() {
+            self.last_touch = toucher;
+        }
+    }
+
+    /// Forget actors destroyed in a frame; their IDs may be reused later on
+    fn apply_deletions(&mut self, frame: &Frame) {
+        for actor in &frame.deleted_actors {
+            self.kinds.remove(actor);
+            self.car_pris.remove(actor);
+            self.component_cars.remove(actor);
+            self.pri_names.remove(actor);
+            self.pri_ids.remove(actor);
+            self.pri_teams.remove(actor);
+            self.rigid_bodies.remove(actor);
+        }
+    }
+
+    /// Name of an entry in the replay's object table
+    pub fn object_name(&self, index: i32) -> &str {
+        usize::try_from(index)
+            .ok()
+            .and_then(|i| self.objects.get(i))
+            .map(String::as_str)
+            .unwrap_or("")
+    }
+}
+
+pub fn distance(a: &Vector3f, b: &Vector3f) -> f32 {
+    let (dx, dy, dz) = (a.x - b.x, a.y - b.y, a.z - b.z);
+    (dx * dx + dy * dy + dz * dz).sqrt()
+}
+
+/// `part` as a percentage of `total`, 0 before any time has been counted
+pub fn percent(part: f32, total: f32) -> f32 {
+    if total > 0.0 {
+        part / total * 100.0
+    } else {
+        0.0
+    }
+}
+
+/// Key identifying a player across the header and the network frames: their
+/// platform ID, falling back to their name for players without one (bots,
+/// split-screen guests)
+pub fn player_key(platform_id: Option<&str>, name: &str) -> String {
+    platform_id
+        .filter(|id| !id.is_empty() && *id != "0" && *id != "unknown")
+        .unwrap_or(name)
+        .to_string()
+}
+
+/// Platform ID carried by a replicated `UniqueId`, in the same form the
+/// header's `PlayerID` uses
+fn remote_id(id: &RemoteId) -> Option<String> {
+    let id = match id {
+        RemoteId::Steam(id) | RemoteId::Xbox(id) | RemoteId::QQ(id) => id.to_string(),
+        RemoteId::PlayStation(ps) => ps.online_id.to_string(),
+        RemoteId::PsyNet(psy) => psy.online_id.to_string(),
+        RemoteId::Switch(switch) => switch.online_id.to_string(),
+        RemoteId::Epic(id) => id.clone(),
+        RemoteId::SplitScreen(_) => return None,
+    };
+    (!id.is_empty() && id != "0").then_some(id)
+}
+
+/// Blue defends negative y; flip orange so both teams attack towards positive y
+pub fn attacking_y(team: u8, y: f32) -> f32 {
+    if team == 1 {
+        -y
+    } else {
+        y
+    }
+}
+
+fn classify(object_name: &str) -> ActorKind {
+    match object_name {
+        "Archetypes.Car.Car_Default" => ActorKind::Car,
+        "TAGame.Default__PRI_TA" => ActorKind::Pri,
+        "Archetypes.CarComponents.CarComponent_Boost" => ActorKind::BoostComponent,
+        "Archetypes.Teams.Team0" => ActorKind::Team(0),
+        "Archetypes.Teams.Team1" => ActorKind::Team(1),
+        name if name.starts_with("Archetypes.Ball.") => ActorKind::Ball,
+        _ => ActorKind::Other,
+    }
+}
+
+/// Stats computed from the network frames, keyed by `player_key`
+/// (team stats are indexed by team number)
+#[derive(Debug, Default)]
+pub struct NetworkStats {
+    pub ball: [BallStats; 2],
+    pub possession: HashMap<String, f32>,
+    pub goals: Vec<GoalEvent>,
+    /// A team forfeited the match
+    pub forfeit: bool,
+    pub game_mode: GameModeInfo,
+    /// Cosmetics each player used, for the team they played on
+    pub loadouts: HashMap<String, Cosmetics>,
+    pub camera_settings: HashMap<String, CameraSettings>,
+    pub camera: HashMap<String, CameraStats>,
+    pub demos: HashMap<String, DemoStats>,
+    pub boost: HashMap<String, BoostStats>,
+    pub movement: HashMap<String, MovementStats>,
+    pub positioning: HashMap<String, PositioningStats>,
+}
+
+/// Walk every network frame once and feed each stat collector
+pub fn analyze(replay: &Replay) -> NetworkStats {
+    let frames = replay
+        .network_frames
+        .as_ref()
+        .map(|nf| nf.frames.as_slice())
+        .unwrap_or(&[]);
+
+    let goals = get_goals(&replay.properties);
+    let mut tracker = ActorTracker::new(&replay.objects);
+    let mut ball = BallCollector::default();
+    let mut demos = DemoCollector::default();
+    let mut boost = BoostCollector::default();
+    let mut movement = MovementCollector::default();
+    let mut positioning = PositioningCollector::default();
+    let mut last_defender = LastDefenderCollector::new(&goals);
+    let mut goal_timeline = GoalCollector::new(&goals);
+    let mut loadouts = LoadoutCollector::default();
+    let mut camera = CameraCollector::default();
+    let mut game_mode = GameModeCollector::default();
+    let mut forfeit = false;
+
+    for frame in frames {
+        tracker.apply_updates(frame);
+        ball.observe(frame, &tracker);
+        demos.observe(frame, &tracker);
+        boost.observe(frame, &tracker);
+        movement.observe(frame, &tracker);
+        positioning.observe(frame, &tracker);
+        last_defender.observe(frame, &tracker);
+        goal_timeline.observe(frame, &tracker);
+        loadouts.observe(frame, &tracker);
+        camera.observe(frame, &tracker);
+        game_mode.observe(frame, &tracker);
+        forfeit |= frame.updated_actors.iter().any(|u| {
+            tracker.attribute_name(u) == "TAGame.GameEvent_Team_TA:bForfeit"
+                && matches!(u.attribute, Attribute::Boolean(true))
+        });
+        tracker.apply_deletions(frame);
+    }
+
+    let mut positioning = positioning.finish();
+    for (name, goals) in last_defender.finish() {
+        positioning
+            .entry(name)
+            .or_default()
+            .goals_against_while_last_defender = goals;
+    }
+
+    let (ball, possession) = ball.finish();
+    let (camera_settings, camera) = camera.finish();
+
+    NetworkStats {
+        ball,
+        possession,
+        goals: goal_timeline.finish(),
+        forfeit,
+        game_mode: game_mode.finish(),
+        loadouts: loadouts.finish(&tracker),
+        camera_settings,
+        camera,
+        demos: demos.finish(),
+        boost: boost.finish(),
+        movement: movement.finish(),
+        positioning,
+    }
+}
@@ -0,0 +1,197 @@
+use super::{distance, percent, ActorTracker, SUPERSONIC_SPEED};
+use crate::types::MovementStats;
+
+use boxcars::{ActorId, Attribute, Frame, Vector3f};
+use std::collections::HashMap;
+
+/// Top speed of a car (uu/s), used for `avg_speed_percentage`
+const MAX_CAR_SPEED: f32 = 2300.0;
+
+/// Speed (uu/s) a car reaches without boosting
+const BOOST_SPEED: f32 = 1410.0;
+
+/// Cars resting on the ground sit at roughly z = 17
+const GROUND_HEIGHT: f32 = 25.0;
+
+/// Height of the goal crossbar; anything above it is high air
+const HIGH_AIR_HEIGHT: f32 = 642.775;
+
+#[derive(Default)]
+struct MovementTotals {
+    time: f32,
+    weighted_speed: f32,
+    distance: f32,
+    time_supersonic: f32,
+    time_boost_speed: f32,
+    time_slow: f32,
+    time_ground: f32,
+    time_low_air: f32,
+    time_high_air: f32,
+    time_powerslide: f32,
+    count_powerslide: u32,
+}
+
+/// Integrates car physics over time to produce speed, height and powerslide stats
+#[derive(Default)]
+pub struct MovementCollector {
+    totals: HashMap<String, MovementTotals>,
+    /// Location of each car on the previous frame
+    last_locations: HashMap<ActorId, Vector3f>,
+    /// Cars currently holding the handbrake
+    handbrakes: HashMap<ActorId, bool>,
+}
+
+impl MovementCollector {
+    pub fn observe(&mut self, frame: &Frame, tracker: &ActorTracker) {
+        for update in &frame.updated_actors {
+            if let ("TAGame.Vehicle_TA:bReplicatedHandbrake", Attribute::Boolean(on)) =
+                (tracker.attribute_name(update), &update.attribute)
+            {
+                let was_on = self
+                    .handbrakes
+                    .insert(update.actor_id, *on)
+                    .unwrap_or(false);
+                if *on && !was_on {
+                    if let Some(player) = tracker.car_player(update.actor_id) {
+                        self.entry(player).count_powerslide += 1;
+                    }
+                }
+            }
+        }
+
+        let delta = frame.delta;
+        for car in tracker.cars() {
+            let (Some(player), Some(rb)) = (tracker.car_player(car), tracker.rigid_body(car))
+            else {
+                continue;
+            };
+
+            let location = rb.location;
+            let speed = tracker.speed(car);
+            let powersliding = self.handbrakes.get(&car).copied().unwrap_or(false);
+            let moved = self
+                .last_locations
+                .insert(car, location)
+                .map(|last| distance(&last, &location))
+                .unwrap_or(0.0);
+
+            let t = self.entry(player);
+            t.time += delta;
+            t.weighted_speed += speed * delta;
+            t.distance += moved;
+
+            if speed >= SUPERSONIC_SPEED {
+                t.time_supersonic += delta;
+            } else if speed >= BOOST_SPEED {
+                t.time_boost_speed += delta;
+            } else {
+                t.time_slow += delta;
+            }
+
+            if location.z <= GROUND_HEIGHT {
+                t.time_ground += delta;
+            } else if location.z <= HIGH_AIR_HEIGHT {
+                t.time_low_air += delta;
+            } else {
+                t.time_high_air += delta;
+            }
+
+            if powersliding {
+                t.time_powerslide += delta;
+            }
+        }
+
+        for actor in &frame.deleted_actors {
+            self.last_locations.remove(actor);
+            self.handbrakes.remove(actor);
+        }
+    }
+
+    pub fn finish(self) -> HashMap<String, MovementStats> {
+        self.totals
+            .into_iter()
+            .map(|(player, t)| (player, t.into_stats()))
+            .collect()
+    }
+
+    fn entry(&mut self, player: &str) -> &mut MovementTotals {
+        self.totals.entry(player.to_string()).or_default()
+    }
+}
+
+impl MovementTotals {
+    fn into_stats(self) -> MovementStats {
+        let avg_speed = if self.time > 0.0 {
+            self.weighted_speed / self.time
+        } else {
+            0.0
+        };
+
+        MovementStats {
+            avg_speed,
+            total_distance: self.distance.round() as u32,
+            time_supersonic_speed: self.time_supersonic,
+            time_boost_speed: self.time_boost_speed,
+            time_slow_speed: self.time_slow,
+            time_ground: self.time_ground,
+            time_low_air: self.time_low_air,
+            time_high_air: self.time_high_air,
+            time_powerslide: self.time_powerslide,
+            count_powerslide: self.count_powerslide,
+            avg_powerslide_duration: if self.count_powerslide > 0 {
+                self.time_powerslide / self.count_powerslide as f32
+            } else {
+                0.0
+            },
+            avg_speed_percentage: avg_speed / MAX_CAR_SPEED * 100.0,
+            percent_slow_speed: percent(self.time_slow, self.time),
+            percent_boost_speed: percent(self.time_boost_speed, self.time),
+            percent_supersonic_speed: percent(self.time_supersonic, self.time),
+            percent_ground: percent(self.time_ground, self.time),
+            percent_low_air: percent(self.time_low_air, self.time),
+            percent_high_air: percent(self.time_high_air, self.time),
+        }
+    }
+}
+
+/// Team movement block; distances are summed with the other totals
+pub fn team_movement<'a>(players: impl IntoIterator<Item = &'a MovementStats>) -> MovementStats {
+    let mut team = MovementStats::default();
+    let mut count = 0.0;
+
+    for p in players {
+        count += 1.0;
+        team.avg_speed += p.avg_speed;
+        team.total_distance += p.total_distance;
+        team.time_supersonic_speed += p.time_supersonic_speed;
+        team.time_boost_speed += p.time_boost_speed;
+        team.time_slow_speed += p.time_slow_speed;
+        team.time_ground += p.time_ground;
+        team.time_low_air += p.time_low_air;
+        team.time_high_air += p.time_high_air;
+        team.time_powerslide += p.time_powerslide;
+        team.count_powerslide += p.count_powerslide;
+        team.avg_powerslide_duration += p.avg_powerslide_duration;
+        team.avg_speed_percentage += p.avg_speed_percentage;
+        team.percent_slow_speed += p.percent_slow_speed;
+        team.percent_boost_speed += p.percent_boost_speed;
+        team.percent_supersonic_speed += p.percent_supersonic_speed;
+        team.percent_ground += p.percent_ground;
+        team.percent_low_air += p.percent_low_air;
+        team.percent_high_air += p.percent_high_air;
+    }
+
+    if count > 0.0 {
+        team.avg_speed /= count;
+        team.avg_powerslide_duration /= count;
+        team.avg_speed_percentage /= count;
+        team.percent_slow_speed /= count;
+        team.percent_boost_speed /= count;
+        team.percent_supersonic_speed /= count;
+        team.percent_ground /= count;
+        team.percent_low_air /= count;
+        team.percent_high_air /= count;
+    }
+
+    team
+}
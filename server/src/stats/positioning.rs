@@ -0,0 +1,254 @@
+use super::{attacking_y, distance, percent, ActorTracker};
+use crate::types::PositioningStats;
+
+use boxcars::{Frame, Vector3f};
+use std::collections::HashMap;
+
+/// Distance (uu) from midfield to the back wall
+const HALF_FIELD_LENGTH: f32 = 5120.0;
+
+#[derive(Default)]
+struct PositioningTotals {
+    time: f32,
+    distance_to_ball: f32,
+    time_possession: f32,
+    distance_to_ball_possession: f32,
+    distance_to_ball_no_possession: f32,
+    time_with_mates: f32,
+    distance_to_mates: f32,
+    time_defensive_third: f32,
+    time_neutral_third: f32,
+    time_offensive_third: f32,
+    time_defensive_half: f32,
+    time_offensive_half: f32,
+    time_behind_ball: f32,
+    time_infront_ball: f32,
+    time_most_back: f32,
+    time_most_forward: f32,
+    time_closest_to_ball: f32,
+    time_farthest_from_ball: f32,
+}
+
+/// A player's car on a single frame, with `y` flipped so that every team
+/// defends negative y
+struct CarSnapshot<'a> {
+    player: &'a str,
+    team: u8,
+    location: Vector3f,
+    y: f32,
+    distance_to_ball: f32,
+}
+
+/// Compares car and ball positions frame by frame
+#[derive(Default)]
+pub struct PositioningCollector {
+    totals: HashMap<String, PositioningTotals>,
+}
+
+impl PositioningCollector {
+    pub fn observe(&mut self, frame: &Frame, tracker: &ActorTracker) {
+        let Some(ball) = tracker.ball().and_then(|b| tracker.rigid_body(b)) else {
+            return;
+        };
+        let ball = ball.location;
+
+        let cars: Vec<CarSnapshot> = tracker
+            .cars()
+            .filter_map(|car| {
+                let player = tracker.car_player(car)?;
+                let team = tracker.car_team(car)?;
+                let location = tracker.rigid_body(car)?.location;
+                Some(CarSnapshot {
+                    player,
+                    team,
+                    location,
+                    y: attacking_y(team, location.y),
+                    distance_to_ball: distance(&location, &ball),
+                })
+            })
+            .collect();
+
+        let delta = frame.delta;
+        let third = HALF_FIELD_LENGTH / 3.0;
+        let possession = tracker.possession();
+
+        for car in &cars {
+            let mates: Vec<&CarSnapshot> = cars
+                .iter()
+                .filter(|c| c.team == car.team && c.player != car.player)
+                .collect();
+            let ball_y = attacking_y(car.team, ball.y);
+
+            let t = self.totals.entry(car.player.to_string()).or_default();
+            t.time += delta;
+            t.distance_to_ball += car.distance_to_ball * delta;
+
+            if possession == Some(car.team) {
+                t.time_possession += delta;
+                t.distance_to_ball_possession += car.distance_to_ball * delta;
+            } else {
+                t.distance_to_ball_no_possession += car.distance_to_ball * delta;
+            }
+
+            if car.y < -third {
+                t.time_defensive_third += delta;
+            } else if car.y > third {
+                t.time_offensive_third += delta;
+            } else {
+                t.time_neutral_third += delta;
+            }
+
+            if car.y < 0.0 {
+                t.time_defensive_half += delta;
+            } else {
+                t.time_offensive_half += delta;
+            }
+
+            if car.y < ball_y {
+                t.time_behind_ball += delta;
+            } else {
+                t.time_infront_ball += delta;
+            }
+
+            // Relative positions only mean something with teammates around
+            if mates.is_empty() {
+                continue;
+            }
+
+            let mates_distance = mates
+                .iter()
+                .map(|m| distance(&car.location, &m.location))
+                .sum::<f32>()
+                / mates.len() as f32;
+            t.time_with_mates += delta;
+            t.distance_to_mates += mates_distance * delta;
+
+            if mates.iter().all(|m| car.y < m.y) {
+                t.time_most_back += delta;
+            }
+            if mates.iter().all(|m| car.y > m.y) {
+                t.time_most_forward += delta;
+            }
+            if mates
+                .iter()
+                .all(|m| car.distance_to_ball < m.distance_to_ball)
+            {
+                t.time_closest_to_ball += delta;
+            }
+            if mates
+                .iter()
+                .all(|m| car.distance_to_ball > m.distance_to_ball)
+            {
+                t.time_farthest_from_ball += delta;
+            }
+        }
+    }
+
+    pub fn finish(self) -> HashMap<String, PositioningStats> {
+        self.totals
+            .into_iter()
+            .map(|(player, t)| (player, t.into_stats()))
+            .collect()
+    }
+}
+
+impl PositioningTotals {
+    fn into_stats(self) -> PositioningStats {
+        let average = |sum: f32, time: f32| if time > 0.0 { sum / time } else { 0.0 };
+
+        PositioningStats {
+            avg_distance_to_ball: average(self.distance_to_ball, self.time),
+            avg_distance_to_ball_possession: average(
+                self.distance_to_ball_possession,
+                self.time_possession,
+            ),
+            avg_distance_to_ball_no_possession: average(
+                self.distance_to_ball_no_possession,
+                self.time - self.time_possession,
+            ),
+            avg_distance_to_mates: average(self.distance_to_mates, self.time_with_mates),
+            time_defensive_third: self.time_defensive_third,
+            time_neutral_third: self.time_neutral_third,
+            time_offensive_third: self.time_offensive_third,
+            time_defensive_half: self.time_defensive_half,
+            time_offensive_half: self.time_offensive_half,
+            time_behind_ball: self.time_behind_ball,
+            time_infront_ball: self.time_infront_ball,
+            time_most_back: self.time_most_back,
+            time_most_forward: self.time_most_forward,
+            time_closest_to_ball: self.time_closest_to_ball,
+            time_farthest_from_ball: self.time_farthest_from_ball,
+            percent_defensive_third: percent(self.time_defensive_third, self.time),
+            percent_offensive_third: percent(self.time_offensive_third, self.time),
+            percent_neutral_third: percent(self.time_neutral_third, self.time),
+            percent_defensive_half: percent(self.time_defensive_half, self.time),
+            percent_offensive_half: percent(self.time_offensive_half, self.time),
+            percent_behind_ball: percent(self.time_behind_ball, self.time),
+            percent_infront_ball: percent(self.time_infront_ball, self.time),
+            percent_most_back: percent(self.time_most_back, self.time),
+            percent_most_forward: percent(self.time_most_forward, self.time),
+            percent_closest_to_ball: percent(self.time_closest_to_ball, self.time),
+            percent_farthest_from_ball: percent(self.time_farthest_from_ball, self.time),
+            goals_against_while_last_defender: 0,
+        }
+    }
+}
+
+/// Team positioning block
+pub fn team_positioning<'a>(
+    players: impl IntoIterator<Item = &'a PositioningStats>,
+) -> PositioningStats {
+    let mut team = PositioningStats::default();
+    let mut count = 0.0;
+
+    for p in players {
+        count += 1.0;
+        team.avg_distance_to_ball += p.avg_distance_to_ball;
+        team.avg_distance_to_ball_possession += p.avg_distance_to_ball_possession;
+        team.avg_distance_to_ball_no_possession += p.avg_distance_to_ball_no_possession;
+        team.avg_distance_to_mates += p.avg_distance_to_mates;
+        team.time_defensive_third += p.time_defensive_third;
+        team.time_neutral_third += p.time_neutral_third;
+        team.time_offensive_third += p.time_offensive_third;
+        team.time_defensive_half += p.time_defensive_half;
+        team.time_offensive_half += p.time_offensive_half;
+        team.time_behind_ball += p.time_behind_ball;
+        team.time_infront_ball += p.time_infront_ball;
+        team.time_most_back += p.time_most_back;
+        team.time_most_forward += p.time_most_forward;
+        team.time_closest_to_ball += p.time_closest_to_ball;
+        team.time_farthest_from_ball += p.time_farthest_from_ball;
+        team.percent_defensive_third += p.percent_defensive_third;
+        team.percent_offensive_third += p.percent_offensive_third;
+        team.percent_neutral_third += p.percent_neutral_third;
+        team.percent_defensive_half += p.percent_defensive_half;
+        team.percent_offensive_half += p.percent_offensive_half;
+        team.percent_behind_ball += p.percent_behind_ball;
+        team.percent_infront_ball += p.percent_infront_ball;
+        team.percent_most_back += p.percent_most_back;
+        team.percent_most_forward += p.percent_most_forward;
+        team.percent_closest_to_ball += p.percent_closest_to_ball;
+        team.percent_farthest_from_ball += p.percent_farthest_from_ball;
+        team.goals_against_while_last_defender += p.goals_against_while_last_defender;
+    }
+
+    if count > 0.0 {
+        team.avg_distance_to_ball /= count;
+        team.avg_distance_to_ball_possession /= count;
+        team.avg_distance_to_ball_no_possession /= count;
+        team.avg_distance_to_mates /= count;
+        team.percent_defensive_third /= count;
+        team.percent_offensive_third /= count;
+        team.percent_neutral_third /= count;
+        team.percent_defensive_half /= count;
+        team.percent_offensive_half /= count;
+        team.percent_behind_ball /= count;
+        team.percent_infront_ball /= count;
+        team.percent_most_back /= count;
+        team.percent_most_forward /= count;
+        team.percent_closest_to_ball /= count;
+        team.percent_farthest_from_ball /= count;
+    }
+
+    team
+}
@@ -1,6 +1,9 @@
-use serde::Serialize;
+use super::common::Location;
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+use ts_rs::TS;
 
-#[derive(Debug, Serialize)]
+#[derive(Debug, Serialize, Deserialize, Clone, TS, JsonSchema)]
 pub struct BallchasingReplay {
     pub id: String,
     pub created: String,
@@ -11,18 +14,50 @@ pub struct BallchasingReplay {
     pub map_code: String,
     pub match_type: String,
     pub team_size: u8,
-    pub playlist_id: String,
+    /// Ballchasing-style playlist, e.g. `ranked-doubles`; `None` when it can't be told
+    pub playlist_id: Option<String>,
     pub duration: f64,
     pub overtime: bool,
     pub overtime_seconds: u32,
+    /// Header `Date` as RFC 3339; empty when it can't be parsed
     pub date: String,
+    /// Whether `date` carries the real offset rather than the recorder's clock labelled as UTC
+    pub date_has_timezone: bool,
     pub blue: BallchasingTeam,
     pub orange: BallchasingTeam,
-    pub playlist_name: String,
-    pub map_name: String,
+    pub playlist_name: Option<String>,
+    /// Display name including the variant, e.g. `Mannfield (Night)`; `None` for unknown codes
+    pub map_name: Option<String>,
+    /// Variant of the map, e.g. `Night` or `Snowy`
+    pub map_variant: Option<String>,
+    pub goals: Vec<GoalEvent>,
+    /// SHA-256 of the uploaded file
+    pub content_hash: String,
+    /// Player who saved the replay
+    pub recorded_by: Option<String>,
+    /// Other recordings of the same match (same `match_guid`) this server has parsed. Only
+    /// the last `MATCH_REGISTRY_SIZE` matches are remembered, and only since startup
+    /// unless `CACHE_DIR` is set
+    pub same_match: Vec<SameMatchReplay>,
+    /// Header or network values the lookup tables don't know
+    pub unresolved: Vec<UnresolvedValue>,
 }
 
-#[derive(Debug, Serialize)]
+/// Another upload of the same match, e.g. recorded by the other team
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq, TS, JsonSchema)]
+pub struct SameMatchReplay {
+    pub id: String,
+    pub recorded_by: Option<String>,
+}
+
+/// A value left out of the replay because no table maps it, e.g. a new map code
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq, TS, JsonSchema)]
+pub struct UnresolvedValue {
+    pub field: String,
+    pub value: String,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, TS, JsonSchema)]
 pub struct BallchasingTeam {
     pub color: String,
     pub name: String,
@@ -30,52 +65,256 @@ pub struct BallchasingTeam {
     pub stats: BallchasingTeamStats,
 }
 
-#[derive(Debug, Serialize, Clone)]
+#[derive(Debug, Serialize, Deserialize, Clone, TS, JsonSchema)]
 pub struct BallchasingPlayer {
     pub name: String,
     pub id: PlayerId,
     pub car_id: u32,
     pub car_name: String,
+    pub loadout: PlayerLoadout,
+    pub camera: Option<CameraSettings>,
     pub stats: PlayerStats,
 }
 
-#[derive(Debug, Serialize, Clone)]
+/// A player's camera profile
+#[derive(Debug, Serialize, Deserialize, Clone, TS, JsonSchema)]
+pub struct CameraSettings {
+    pub fov: f32,
+    pub height: f32,
+    pub pitch: f32,
+    pub distance: f32,
+    pub stiffness: f32,
+    pub swivel_speed: f32,
+    /// Only replicated by newer replays
+    pub transition_speed: Option<f32>,
+}
+
+/// Cosmetics a player used, for the team they played on
+#[derive(Debug, Serialize, Deserialize, Clone, Default, TS, JsonSchema)]
+pub struct PlayerLoadout {
+    pub body: Option<LoadoutItem>,
+    pub decal: Option<LoadoutItem>,
+    pub wheels: Option<LoadoutItem>,
+    pub boost: Option<LoadoutItem>,
+    pub antenna: Option<LoadoutItem>,
+    pub topper: Option<LoadoutItem>,
+    pub engine_audio: Option<LoadoutItem>,
+    pub trail: Option<LoadoutItem>,
+    pub goal_explosion: Option<LoadoutItem>,
+    pub banner: Option<LoadoutItem>,
+    pub paint: Option<CarPaint>,
+}
+
+/// An equipped product and the attributes (paint, special edition, ...) applied to it
+#[derive(Debug, Serialize, Deserialize, Clone, TS, JsonSchema)]
+pub struct LoadoutItem {
+    pub id: u32,
+    pub name: Option<String>,
+    pub attributes: Vec<ProductAttribute>,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, TS, JsonSchema)]
+pub struct ProductAttribute {
+    /// `painted`, `special_edition`, `team_edition`, `color`, `title`, `certified`, or
+    /// the attribute class for other kinds the replay doesn't carry a value for
+    pub kind: String,
+    pub id: Option<u32>,
+    pub name: Option<String>,
+}
+
+/// Team colors and finishes of a player's car
+#[derive(Debug, Serialize, Deserialize, Clone, TS, JsonSchema)]
+pub struct CarPaint {
+    /// Index into the team's primary color swatches
+    pub primary_color: u8,
+    /// Index into the accent color swatches
+    pub accent_color: u8,
+    pub primary_finish: LoadoutItem,
+    pub accent_finish: LoadoutItem,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, TS, JsonSchema)]
 pub struct PlayerId {
     pub platform: String,
     pub id: String,
 }
 
-#[derive(Debug, Serialize, Clone)]
+#[derive(Debug, Serialize, Deserialize, Clone, TS, JsonSchema)]
 pub struct PlayerStats {
+    pub ball: PlayerBallStats,
     pub core: CoreStats,
     pub boost: BoostStats,
+    pub movement: MovementStats,
+    pub positioning: PositioningStats,
     pub demo: DemoStats,
+    pub camera: CameraStats,
 }
 
-#[derive(Debug, Serialize, Clone)]
+/// One player's entry in the `/camera` summary
+#[derive(Debug, Serialize, Deserialize, Clone, TS, JsonSchema)]
+pub struct PlayerCamera {
+    pub name: String,
+    pub id: PlayerId,
+    pub team: String,
+    pub camera: Option<CameraSettings>,
+    pub stats: CameraStats,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, Default, TS, JsonSchema)]
+pub struct CameraStats {
+    pub time_ball_cam: f32,
+    pub percent_ball_cam: f32,
+    pub count_ball_cam_toggles: u32,
+    pub time_behind_view: f32,
+    pub percent_behind_view: f32,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, Default, TS, JsonSchema)]
 pub struct CoreStats {
     pub shots: u32,
+    pub shots_against: u32,
     pub goals: u32,
+    pub goals_against: u32,
     pub saves: u32,
     pub assists: u32,
     pub score: u32,
-    pub mvp: bool, // TODO ❗
+    pub mvp: bool,
     pub shooting_percentage: u32,
 }
 
-#[derive(Debug, Serialize, Clone)]
+#[derive(Debug, Serialize, Deserialize, Clone, Default, TS, JsonSchema)]
 pub struct BoostStats {
-    pub bpm: u32, // TODO ❗
+    pub bpm: f32,
+    pub bcpm: f32,
+    pub avg_amount: f32,
+    pub amount_collected: u32,
+    pub amount_stolen: u32,
+    pub amount_collected_big: u32,
+    pub amount_stolen_big: u32,
+    pub amount_collected_small: u32,
+    pub amount_stolen_small: u32,
+    pub count_collected_big: u32,
+    pub count_stolen_big: u32,
+    pub count_collected_small: u32,
+    pub count_stolen_small: u32,
+    pub amount_overfill: u32,
+    pub amount_overfill_stolen: u32,
+    pub amount_used_while_supersonic: u32,
+    pub time_zero_boost: f32,
+    pub percent_zero_boost: f32,
+    pub time_full_boost: f32,
+    pub percent_full_boost: f32,
+    pub time_boost_0_25: f32,
+    pub time_boost_25_50: f32,
+    pub time_boost_50_75: f32,
+    pub time_boost_75_100: f32,
+    pub percent_boost_0_25: f32,
+    pub percent_boost_25_50: f32,
+    pub percent_boost_50_75: f32,
+    pub percent_boost_75_100: f32,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, Default, TS, JsonSchema)]
+pub struct MovementStats {
+    pub avg_speed: f32,
+    pub total_distance: u32,
+    pub time_supersonic_speed: f32,
+    pub time_boost_speed: f32,
+    pub time_slow_speed: f32,
+    pub time_ground: f32,
+    pub time_low_air: f32,
+    pub time_high_air: f32,
+    pub time_powerslide: f32,
+    pub count_powerslide: u32,
+    pub avg_powerslide_duration: f32,
+    pub avg_speed_percentage: f32,
+    pub percent_slow_speed: f32,
+    pub percent_boost_speed: f32,
+    pub percent_supersonic_speed: f32,
+    pub percent_ground: f32,
+    pub percent_low_air: f32,
+    pub percent_high_air: f32,
 }
 
-#[derive(Debug, Serialize, Clone)]
+#[derive(Debug, Serialize, Deserialize, Clone, Default, TS, JsonSchema)]
+pub struct PositioningStats {
+    pub avg_distance_to_ball: f32,
+    pub avg_distance_to_ball_possession: f32,
+    pub avg_distance_to_ball_no_possession: f32,
+    pub avg_distance_to_mates: f32,
+    pub time_defensive_third: f32,
+    pub time_neutral_third: f32,
+    pub time_offensive_third: f32,
+    pub time_defensive_half: f32,
+    pub time_offensive_half: f32,
+    pub time_behind_ball: f32,
+    pub time_infront_ball: f32,
+    pub time_most_back: f32,
+    pub time_most_forward: f32,
+    pub time_closest_to_ball: f32,
+    pub time_farthest_from_ball: f32,
+    pub percent_defensive_third: f32,
+    pub percent_offensive_third: f32,
+    pub percent_neutral_third: f32,
+    pub percent_defensive_half: f32,
+    pub percent_offensive_half: f32,
+    pub percent_behind_ball: f32,
+    pub percent_infront_ball: f32,
+    pub percent_most_back: f32,
+    pub percent_most_forward: f32,
+    pub percent_closest_to_ball: f32,
+    pub percent_farthest_from_ball: f32,
+    pub goals_against_while_last_defender: u32,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, Default, TS, JsonSchema)]
 pub struct DemoStats {
-    pub inflicted: u32, // TODO ❗
-    pub taken: u32,     // TODO ❗
+    pub inflicted: u32,
+    pub taken: u32,
 }
 
-#[derive(Debug, Serialize)]
+#[derive(Debug, Serialize, Deserialize, Clone, TS, JsonSchema)]
 pub struct BallchasingTeamStats {
+    pub ball: BallStats,
     pub core: CoreStats,
+    pub boost: BoostStats,
+    pub movement: MovementStats,
+    pub positioning: PositioningStats,
     pub demo: DemoStats,
 }
+
+#[derive(Debug, Serialize, Deserialize, Clone, Default, TS, JsonSchema)]
+pub struct BallStats {
+    pub possession_time: f32,
+    pub time_in_side: f32,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, Default, TS, JsonSchema)]
+pub struct PlayerBallStats {
+    pub possession_time: f32,
+}
+
+/// A goal on the match timeline
+#[derive(Debug, Serialize, Deserialize, Clone, TS, JsonSchema)]
+pub struct GoalEvent {
+    pub frame: u32,
+    /// Replay time (seconds) of the goal frame
+    pub time: f32,
+    /// Game clock at the moment of the goal; stays at 0 during overtime
+    pub seconds_remaining: Option<i32>,
+    pub overtime: bool,
+    pub team: String,
+    pub scorer: String,
+    pub assister: Option<String>,
+    /// Score after this goal
+    pub score: Scoreline,
+    /// Ball speed in km/h as it crossed the line
+    pub ball_speed: f32,
+    pub ball_location: Option<Location>,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, Default, TS, JsonSchema)]
+pub struct Scoreline {
+    pub blue: u32,
+    pub orange: u32,
+}
@@ -1,39 +1,48 @@
-use serde::Serialize;
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+use ts_rs::TS;
 
-#[derive(Debug, Serialize)]
+#[derive(Debug, Serialize, Deserialize, Clone, TS, JsonSchema)]
 pub struct Location {
     pub x: f32,
     pub y: f32,
     pub z: f32,
 }
 
-#[derive(Debug, Serialize)]
+#[derive(Debug, Serialize, JsonSchema)]
 pub struct Rotation {
     pub yaw: Option<f32>,
     pub pitch: Option<f32>,
     pub roll: Option<f32>,
 }
 
-#[derive(Debug, Serialize)]
+#[derive(Debug, Serialize, JsonSchema)]
 pub struct Index {
     pub class: String,
     pub index: usize,
 }
 
-#[derive(Debug, Serialize)]
+#[derive(Debug, Serialize, JsonSchema)]
 pub struct Keyframe {
     pub time: f32,
     pub frame: u32,
     pub position: usize,
 }
 
-#[derive(Debug, Serialize)]
+#[derive(Debug, Serialize, JsonSchema)]
 pub struct TickMark {
     pub description: String,
     pub frame: u32,
 }
 
-#[derive(Debug, Serialize)]
+#[derive(Debug, Serialize, JsonSchema)]
+pub struct DebugInfo {
+    pub frame: u32,
+    pub user: String,
+    pub text: String,
+}
+
+#[derive(Debug, Serialize, JsonSchema)]
 pub struct NetCache {
     pub object_ind: u32,
     pub parent_id: u32,
@@ -41,20 +50,20 @@ pub struct NetCache {
     pub properties: Vec<Property>,
 }
 
-#[derive(Debug, Serialize)]
+#[derive(Debug, Serialize, JsonSchema)]
 pub struct Property {
     pub object_ind: u32,
     pub stream_id: u32,
 }
 
-#[derive(Debug, Serialize)]
+#[derive(Debug, Serialize, JsonSchema)]
 pub struct Vector3 {
     pub x: f32,
     pub y: f32,
     pub z: f32,
 }
 
-#[derive(Debug, Serialize)]
+#[derive(Debug, Serialize, JsonSchema)]
 pub struct RotationQuat {
     pub x: f32,
     pub y: f32,
@@ -62,14 +71,170 @@ pub struct RotationQuat {
     pub w: f32,
 }
 
-#[derive(Debug, Serialize)]
-pub struct RemoteId {
-    pub steam: String,
+/// Platform account of a player; 64-bit IDs are strings so JavaScript can't round them
+#[derive(Debug, Serialize, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum RemoteId {
+    PlayStation {
+        online_id: String,
+        name: String,
+    },
+    PsyNet {
+        online_id: String,
+    },
+    SplitScreen(u32),
+    Steam(String),
+    Switch {
+        online_id: String,
+    },
+    Xbox(String),
+    #[serde(rename = "qq")]
+    QQ(String),
+    Epic(String),
 }
 
-#[derive(Debug, Serialize)]
+#[derive(Debug, Serialize, JsonSchema)]
 pub struct UniqueId {
     pub system_id: u8,
     pub remote_id: RemoteId,
     pub local_id: u32,
 }
+
+impl From<&boxcars::Vector3f> for Location {
+    fn from(v: &boxcars::Vector3f) -> Self {
+        Location {
+            x: v.x,
+            y: v.y,
+            z: v.z,
+        }
+    }
+}
+
+impl From<&boxcars::Vector3i> for Location {
+    fn from(v: &boxcars::Vector3i) -> Self {
+        Location {
+            x: v.x as f32,
+            y: v.y as f32,
+            z: v.z as f32,
+        }
+    }
+}
+
+impl From<&boxcars::Vector3f> for Vector3 {
+    fn from(v: &boxcars::Vector3f) -> Self {
+        Vector3 {
+            x: v.x,
+            y: v.y,
+            z: v.z,
+        }
+    }
+}
+
+impl From<&boxcars::Quaternion> for RotationQuat {
+    fn from(q: &boxcars::Quaternion) -> Self {
+        RotationQuat {
+            x: q.x,
+            y: q.y,
+            z: q.z,
+            w: q.w,
+        }
+    }
+}
+
+impl From<&boxcars::Rotation> for Rotation {
+    fn from(r: &boxcars::Rotation) -> Self {
+        Rotation {
+            yaw: r.yaw.map(f32::from),
+            pitch: r.pitch.map(f32::from),
+            roll: r.roll.map(f32::from),
+        }
+    }
+}
+
+impl From<&boxcars::ClassIndex> for Index {
+    fn from(c: &boxcars::ClassIndex) -> Self {
+        Index {
+            class: c.class.clone(),
+            index: c.index as usize,
+        }
+    }
+}
+
+impl From<&boxcars::KeyFrame> for Keyframe {
+    fn from(k: &boxcars::KeyFrame) -> Self {
+        Keyframe {
+            time: k.time,
+            frame: k.frame as u32,
+            position: k.position as usize,
+        }
+    }
+}
+
+impl From<&boxcars::TickMark> for TickMark {
+    fn from(t: &boxcars::TickMark) -> Self {
+        TickMark {
+            description: t.description.clone(),
+            frame: t.frame as u32,
+        }
+    }
+}
+
+impl From<&boxcars::DebugInfo> for DebugInfo {
+    fn from(d: &boxcars::DebugInfo) -> Self {
+        DebugInfo {
+            frame: d.frame as u32,
+            user: d.user.clone(),
+            text: d.text.clone(),
+        }
+    }
+}
+
+impl From<&boxcars::ClassNetCache> for NetCache {
+    fn from(c: &boxcars::ClassNetCache) -> Self {
+        NetCache {
+            object_ind: c.object_ind as u32,
+            parent_id: c.parent_id as u32,
+            cache_id: c.cache_id as u32,
+            properties: c
+                .properties
+                .iter()
+                .map(|p| Property {
+                    object_ind: p.object_ind as u32,
+                    stream_id: p.stream_id as u32,
+                })
+                .collect(),
+        }
+    }
+}
+
+impl From<&boxcars::RemoteId> for RemoteId {
+    fn from(id: &boxcars::RemoteId) -> Self {
+        match id {
+            boxcars::RemoteId::PlayStation(ps) => RemoteId::PlayStation {
+                online_id: ps.online_id.to_string(),
+                name: ps.name.clone(),
+            },
+            boxcars::RemoteId::PsyNet(psy) => RemoteId::PsyNet {
+                online_id: psy.online_id.to_string(),
+            },
+            boxcars::RemoteId::SplitScreen(id) => RemoteId::SplitScreen(*id),
+            boxcars::RemoteId::Steam(id) => RemoteId::Steam(id.to_string()),
+            boxcars::RemoteId::Switch(switch) => RemoteId::Switch {
+                online_id: switch.online_id.to_string(),
+            },
+            boxcars::RemoteId::Xbox(id) => RemoteId::Xbox(id.to_string()),
+            boxcars::RemoteId::QQ(id) => RemoteId::QQ(id.to_string()),
+            boxcars::RemoteId::Epic(id) => RemoteId::Epic(id.clone()),
+        }
+    }
+}
+
+impl From<&boxcars::UniqueId> for UniqueId {
+    fn from(id: &boxcars::UniqueId) -> Self {
+        UniqueId {
+            system_id: id.system_id,
+            remote_id: (&id.remote_id).into(),
+            local_id: u32::from(id.local_id),
+        }
+    }
+}
@@ -1,14 +1,15 @@
 use super::common::*;
+use schemars::JsonSchema;
 use serde::Serialize;
 
 /// Top-level network frames structure
-#[derive(Debug, Serialize)]
+#[derive(Debug, Serialize, JsonSchema)]
 pub struct NetworkFrames {
     pub frames: Vec<Frame>,
 }
 
 /// Represents a single network frame
-#[derive(Debug, Serialize)]
+#[derive(Debug, Serialize, JsonSchema)]
 pub struct Frame {
     pub time: f32,
     pub delta: f32,
@@ -18,23 +19,23 @@ pub struct Frame {
 }
 
 /// Represents an actor created during a frame
-#[derive(Debug, Serialize)]
+#[derive(Debug, Serialize, JsonSchema)]
 pub struct NewActor {
     pub actor_id: u32,
-    pub name_id: u32,
+    pub name_id: Option<u32>,
     pub object_id: u32,
     pub initial_trajectory: InitialTrajectory,
 }
 
 /// Represents the starting location/rotation of a new actor
-#[derive(Debug, Serialize)]
+#[derive(Debug, Serialize, JsonSchema)]
 pub struct InitialTrajectory {
     pub location: Option<Location>,
     pub rotation: Option<Rotation>,
 }
 
 /// Represents an actor that was updated in a frame
-#[derive(Debug, Serialize)]
+#[derive(Debug, Serialize, JsonSchema)]
 pub struct UpdatedActor {
     pub actor_id: u32,
     pub stream_id: u32,
@@ -42,53 +43,80 @@ pub struct UpdatedActor {
     pub attribute: Attribute,
 }
 
-/// Dynamic attribute data associated with an updated actor
-#[derive(Debug, Serialize)]
-pub struct Attribute {
-    pub active_actor: Option<ActiveActor>,
-    pub string: Option<String>,
-    pub boolean: Option<bool>,
-    pub reservation: Option<Reservation>,
-    pub int: Option<i32>,
-    pub rigid_body: Option<RigidBody>,
-    pub byte: Option<u8>,
-    pub unique_id: Option<UniqueId2>,
-    pub int64: Option<String>,
-    pub float: Option<f32>,
-    pub party_leader: Option<PartyLeader>,
-    pub loadouts_online: Option<LoadoutsOnline>,
-    pub team_loadout: Option<TeamLoadout>,
-    pub cam_settings: Option<CamSettings>,
-    pub team_paint: Option<TeamPaint>,
-    pub replicated_boost: Option<ReplicatedBoost>,
-    pub location: Option<Location>,
-    pub pickup_new: Option<PickupNew>,
-    pub enum_val: Option<u8>,
-    pub demolish_extended: Option<DemolishExtended>,
-    pub stat_event: Option<StatEvent>,
-    pub extended_explosion: Option<ExtendedExplosion>,
+/// Dynamic attribute data associated with an updated actor, serialized as a
+/// single-key object such as `{ "rigid_body": { ... } }`
+#[derive(Debug, Serialize, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum Attribute {
+    Boolean(bool),
+    Byte(u8),
+    AppliedDamage(AppliedDamage),
+    DamageState(DamageState),
+    CamSettings(CamSettings),
+    ClubColors(ClubColors),
+    Demolish(Demolish),
+    DemolishExtended(DemolishExtended),
+    DemolishFx(DemolishFx),
+    #[serde(rename = "enum_val")]
+    Enum(u16),
+    Explosion(Explosion),
+    ExtendedExplosion(ExtendedExplosion),
+    FlaggedByte(FlaggedByte),
+    ActiveActor(ActiveActor),
+    Float(f32),
+    GameMode(GameMode),
+    Int(i32),
+    /// 64-bit values are strings so JavaScript can't round them
+    Int64(String),
+    Loadout(LoadoutConfig),
+    TeamLoadout(TeamLoadout),
+    Location(Location),
+    MusicStinger(MusicStinger),
+    PlayerHistoryKey(u16),
+    Pickup(Pickup),
+    PickupNew(PickupNew),
+    #[serde(rename = "qword")]
+    QWord(String),
+    Welded(Welded),
+    Title(Title),
+    TeamPaint(TeamPaint),
+    RigidBody(RigidBody),
+    String(String),
+    UniqueId(UniqueId),
+    Reservation(Reservation),
+    PartyLeader(Option<UniqueId>),
+    PrivateMatch(PrivateMatchSettings),
+    LoadoutOnline(Vec<Vec<LoadoutValue>>),
+    LoadoutsOnline(LoadoutsOnline),
+    StatEvent(StatEvent),
+    Rotation(Rotation),
+    RepStatTitle(RepStatTitle),
+    PickupInfo(PickupInfo),
+    Impulse(Impulse),
+    ReplicatedBoost(ReplicatedBoost),
+    LogoData(LogoData),
 }
 
 /// Whether another actor is active
-#[derive(Debug, Serialize)]
+#[derive(Debug, Serialize, JsonSchema)]
 pub struct ActiveActor {
     pub active: bool,
     pub actor: u32,
 }
 
 /// Player reservation struct
-#[derive(Debug, Serialize)]
+#[derive(Debug, Serialize, JsonSchema)]
 pub struct Reservation {
     pub number: u32,
     pub unique_id: UniqueId,
-    pub name: String,
+    pub name: Option<String>,
     pub unknown1: bool,
     pub unknown2: bool,
-    pub unknown3: u32,
+    pub unknown3: Option<u8>,
 }
 
 /// Rigid body physics
-#[derive(Debug, Serialize)]
+#[derive(Debug, Serialize, JsonSchema)]
 pub struct RigidBody {
     pub sleeping: bool,
     pub location: Location,
@@ -97,22 +125,7 @@ pub struct RigidBody {
     pub angular_velocity: Option<Vector3>,
 }
 
-/// Actor ID + platform combo
-#[derive(Debug, Serialize)]
-pub struct UniqueId2 {
-    pub system_id: u8,
-    pub remote_id: RemoteId,
-    pub local_id: u32,
-}
-
-#[derive(Debug, Serialize)]
-pub struct PartyLeader {
-    pub system_id: u8,
-    pub remote_id: RemoteId,
-    pub local_id: u32,
-}
-
-#[derive(Debug, Serialize)]
+#[derive(Debug, Serialize, JsonSchema)]
 pub struct LoadoutsOnline {
     pub blue: Vec<Vec<LoadoutValue>>,
     pub orange: Vec<Vec<LoadoutValue>>,
@@ -120,27 +133,36 @@ pub struct LoadoutsOnline {
     pub unknown2: bool,
 }
 
-#[derive(Debug, Serialize)]
+#[derive(Debug, Serialize, JsonSchema)]
 pub struct LoadoutValue {
     pub unknown: bool,
     pub object_ind: u32,
     pub value: LoadoutDetail,
 }
 
-#[derive(Debug, Serialize)]
-pub struct LoadoutDetail {
-    pub new_paint: Option<u32>,
-    pub new_color: Option<u32>,
-    pub title: Option<String>,
+/// Value of a product attribute (paint, certification, title, ...)
+#[derive(Debug, Serialize, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum LoadoutDetail {
+    NoColor,
+    Absent,
+    OldColor(u32),
+    NewColor(u32),
+    OldPaint(u32),
+    NewPaint(u32),
+    Title(String),
+    SpecialEdition(u32),
+    OldTeamEdition(u32),
+    NewTeamEdition(u32),
 }
 
-#[derive(Debug, Serialize)]
+#[derive(Debug, Serialize, JsonSchema)]
 pub struct TeamLoadout {
     pub blue: LoadoutConfig,
     pub orange: LoadoutConfig,
 }
 
-#[derive(Debug, Serialize)]
+#[derive(Debug, Serialize, JsonSchema)]
 pub struct LoadoutConfig {
     pub version: u32,
     pub body: u32,
@@ -150,15 +172,15 @@ pub struct LoadoutConfig {
     pub antenna: u32,
     pub topper: u32,
     pub unknown1: u32,
-    pub unknown2: u32,
-    pub engine_audio: u32,
-    pub trail: u32,
-    pub goal_explosion: u32,
-    pub banner: u32,
-    pub product_id: u32,
+    pub unknown2: Option<u32>,
+    pub engine_audio: Option<u32>,
+    pub trail: Option<u32>,
+    pub goal_explosion: Option<u32>,
+    pub banner: Option<u32>,
+    pub product_id: Option<u32>,
 }
 
-#[derive(Debug, Serialize)]
+#[derive(Debug, Serialize, JsonSchema)]
 pub struct CamSettings {
     pub fov: f32,
     pub height: f32,
@@ -166,10 +188,10 @@ pub struct CamSettings {
     pub distance: f32,
     pub stiffness: f32,
     pub swivel: f32,
-    pub transition: f32,
+    pub transition: Option<f32>,
 }
 
-#[derive(Debug, Serialize)]
+#[derive(Debug, Serialize, JsonSchema)]
 pub struct TeamPaint {
     pub team: u32,
     pub primary_color: u32,
@@ -178,54 +200,517 @@ pub struct TeamPaint {
     pub accent_finish: u32,
 }
 
-#[derive(Debug, Serialize)]
+#[derive(Debug, Serialize, JsonSchema)]
 pub struct ReplicatedBoost {
     pub grant_count: u32,
-    pub boost_amount: f32,
+    /// Raw boost amount, 0-255
+    pub boost_amount: u32,
     pub unused1: u32,
     pub unused2: u32,
 }
 
-#[derive(Debug, Serialize)]
+#[derive(Debug, Serialize, JsonSchema)]
+pub struct Pickup {
+    pub instigator: Option<u32>,
+    pub picked_up: bool,
+}
+
+#[derive(Debug, Serialize, JsonSchema)]
 pub struct PickupNew {
     pub instigator: Option<u32>,
     pub picked_up: u32,
 }
 
-#[derive(Debug, Serialize)]
+#[derive(Debug, Serialize, JsonSchema)]
+pub struct PickupInfo {
+    pub active: bool,
+    pub actor: u32,
+    pub items_are_preview: bool,
+    pub unknown: bool,
+    pub unknown2: bool,
+}
+
+#[derive(Debug, Serialize, JsonSchema)]
+pub struct Demolish {
+    pub attacker_flag: bool,
+    pub attacker: u32,
+    pub victim_flag: bool,
+    pub victim: u32,
+    pub attack_velocity: Vector3,
+    pub victim_velocity: Vector3,
+}
+
+#[derive(Debug, Serialize, JsonSchema)]
+pub struct DemolishFx {
+    pub custom_demo_flag: bool,
+    pub custom_demo_id: i32,
+    pub attacker_flag: bool,
+    pub attacker: u32,
+    pub victim_flag: bool,
+    pub victim: u32,
+    pub attack_velocity: Vector3,
+    pub victim_velocity: Vector3,
+}
+
+#[derive(Debug, Serialize, JsonSchema)]
 pub struct DemolishExtended {
-    pub attacker_pri: ActorRef,
-    pub self_demo: ActorRef,
+    pub attacker_pri: ActiveActor,
+    pub self_demo: ActiveActor,
     pub self_demolish: bool,
-    pub goal_explosion_owner: ActorRef,
-    pub attacker: ActorRef,
-    pub victim: ActorRef,
+    pub goal_explosion_owner: ActiveActor,
+    pub attacker: ActiveActor,
+    pub victim: ActiveActor,
     pub attacker_velocity: Vector3,
     pub victim_velocity: Vector3,
 }
 
-#[derive(Debug, Serialize)]
-pub struct ActorRef {
+#[derive(Debug, Serialize, JsonSchema)]
+pub struct AppliedDamage {
+    pub id: u8,
+    pub position: Location,
+    pub damage_index: i32,
+    pub total_damage: i32,
+}
+
+/// Dropshot tile damage
+#[derive(Debug, Serialize, JsonSchema)]
+pub struct DamageState {
+    pub tile_state: u8,
+    pub damaged: bool,
+    pub offender: u32,
+    pub ball_position: Location,
+    pub direct_hit: bool,
+    pub unknown1: bool,
+}
+
+#[derive(Debug, Serialize, JsonSchema)]
+pub struct ClubColors {
+    pub blue_flag: bool,
+    pub blue_color: u8,
+    pub orange_flag: bool,
+    pub orange_color: u8,
+}
+
+#[derive(Debug, Serialize, JsonSchema)]
+pub struct FlaggedByte {
+    pub flag: bool,
+    pub byte: u8,
+}
+
+#[derive(Debug, Serialize, JsonSchema)]
+pub struct GameMode {
+    /// Number of bits the mode was encoded with (depends on the replay version)
+    pub bits: u8,
+    pub mode: u8,
+}
+
+#[derive(Debug, Serialize, JsonSchema)]
+pub struct MusicStinger {
+    pub flag: bool,
+    pub cue: u32,
+    pub trigger: u8,
+}
+
+#[derive(Debug, Serialize, JsonSchema)]
+pub struct Welded {
     pub active: bool,
     pub actor: u32,
+    pub offset: Vector3,
+    pub mass: f32,
+    pub rotation: Rotation,
 }
 
-#[derive(Debug, Serialize)]
+/// Undocumented title attribute, kept field by field
+#[derive(Debug, Serialize, JsonSchema)]
+pub struct Title {
+    pub unknown1: bool,
+    pub unknown2: bool,
+    pub unknown3: u32,
+    pub unknown4: u32,
+    pub unknown5: u32,
+    pub unknown6: u32,
+    pub unknown7: u32,
+    pub unknown8: bool,
+}
+
+#[derive(Debug, Serialize, JsonSchema)]
+pub struct PrivateMatchSettings {
+    pub mutators: String,
+    pub joinable_by: u32,
+    pub max_players: u32,
+    pub game_name: String,
+    pub password: String,
+    pub flag: bool,
+}
+
+#[derive(Debug, Serialize, JsonSchema)]
 pub struct StatEvent {
     pub unknown1: bool,
-    pub object_id: u32,
+    pub object_id: i32,
+}
+
+#[derive(Debug, Serialize, JsonSchema)]
+pub struct RepStatTitle {
+    pub unknown: bool,
+    pub name: String,
+    pub unknown2: bool,
+    pub index: u32,
+    pub value: u32,
+}
+
+#[derive(Debug, Serialize, JsonSchema)]
+pub struct Impulse {
+    pub compressed_rotation: i32,
+    pub speed: f32,
+}
+
+#[derive(Debug, Serialize, JsonSchema)]
+pub struct LogoData {
+    pub logo_id: u32,
+    pub swap_colors: bool,
 }
 
-#[derive(Debug, Serialize)]
+#[derive(Debug, Serialize, JsonSchema)]
 pub struct ExtendedExplosion {
     pub explosion: Explosion,
     pub unknown1: bool,
     pub secondary_actor: u32,
 }
 
-#[derive(Debug, Serialize)]
+#[derive(Debug, Serialize, JsonSchema)]
 pub struct Explosion {
     pub flag: bool,
     pub actor: u32,
     pub location: Location,
 }
+
+fn actor(id: boxcars::ActorId) -> u32 {
+    id.0 as u32
+}
+
+impl From<&boxcars::NetworkFrames> for NetworkFrames {
+    fn from(nf: &boxcars::NetworkFrames) -> Self {
+        NetworkFrames {
+            frames: nf.frames.iter().map(Frame::from).collect(),
+        }
+    }
+}
+
+impl From<&boxcars::Frame> for Frame {
+    fn from(f: &boxcars::Frame) -> Self {
+        Frame {
+            time: f.time,
+            delta: f.delta,
+            new_actors: f.new_actors.iter().map(NewActor::from).collect(),
+            deleted_actors: f.deleted_actors.iter().copied().map(actor).collect(),
+            updated_actors: f.updated_actors.iter().map(UpdatedActor::from).collect(),
+        }
+    }
+}
+
+impl From<&boxcars::NewActor> for NewActor {
+    fn from(a: &boxcars::NewActor) -> Self {
+        NewActor {
+            actor_id: actor(a.actor_id),
+            name_id: a.name_id.map(|id| id as u32),
+            object_id: a.object_id.0 as u32,
+            initial_trajectory: InitialTrajectory {
+                location: a.initial_trajectory.location.as_ref().map(Location::from),
+                rotation: a.initial_trajectory.rotation.as_ref().map(Rotation::from),
+            },
+        }
+    }
+}
+
+impl From<&boxcars::UpdatedAttribute> for UpdatedActor {
+    fn from(u: &boxcars::UpdatedAttribute) -> Self {
+        UpdatedActor {
+            actor_id: actor(u.actor_id),
+            stream_id: u.stream_id.0 as u32,
+            object_id: u.object_id.0 as u32,
+            attribute: (&u.attribute).into(),
+        }
+    }
+}
+
+impl From<&boxcars::Attribute> for Attribute {
+    fn from(a: &boxcars::Attribute) -> Self {
+        use boxcars::Attribute as A;
+
+        match a {
+            A::Boolean(b) => Attribute::Boolean(*b),
+            A::Byte(b) => Attribute::Byte(*b),
+            A::AppliedDamage(d) => Attribute::AppliedDamage(AppliedDamage {
+                id: d.id,
+                position: (&d.position).into(),
+                damage_index: d.damage_index,
+                total_damage: d.total_damage,
+            }),
+            A::DamageState(d) => Attribute::DamageState(DamageState {
+                tile_state: d.tile_state,
+                damaged: d.damaged,
+                offender: actor(d.offender),
+                ball_position: (&d.ball_position).into(),
+                direct_hit: d.direct_hit,
+                unknown1: d.unknown1,
+            }),
+            A::CamSettings(c) => Attribute::CamSettings(CamSettings {
+                fov: c.fov,
+                height: c.height,
+                angle: c.angle,
+                distance: c.distance,
+                stiffness: c.stiffness,
+                swivel: c.swivel,
+                transition: c.transition,
+            }),
+            A::ClubColors(c) => Attribute::ClubColors(ClubColors {
+                blue_flag: c.blue_flag,
+                blue_color: c.blue_color,
+                orange_flag: c.orange_flag,
+                orange_color: c.orange_color,
+            }),
+            A::Demolish(d) => Attribute::Demolish(Demolish {
+                attacker_flag: d.attacker_flag,
+                attacker: actor(d.attacker),
+                victim_flag: d.victim_flag,
+                victim: actor(d.victim),
+                attack_velocity: (&d.attack_velocity).into(),
+                victim_velocity: (&d.victim_velocity).into(),
+            }),
+            A::DemolishExtended(d) => Attribute::DemolishExtended(DemolishExtended {
+                attacker_pri: (&d.attacker_pri).into(),
+                self_demo: (&d.self_demo).into(),
+                self_demolish: d.self_demolish,
+                goal_explosion_owner: (&d.goal_explosion_owner).into(),
+                attacker: (&d.attacker).into(),
+                victim: (&d.victim).into(),
+                attacker_velocity: (&d.attacker_velocity).into(),
+                victim_velocity: (&d.victim_velocity).into(),
+            }),
+            A::DemolishFx(d) => Attribute::DemolishFx(DemolishFx {
+                custom_demo_flag: d.custom_demo_flag,
+                custom_demo_id: d.custom_demo_id,
+                attacker_flag: d.attacker_flag,
+                attacker: actor(d.attacker),
+                victim_flag: d.victim_flag,
+                victim: actor(d.victim),
+                attack_velocity: (&d.attack_velocity).into(),
+                victim_velocity: (&d.victim_velocity).into(),
+            }),
+            A::Enum(e) => Attribute::Enum(*e),
+            A::Explosion(e) => Attribute::Explosion(e.into()),
+            A::ExtendedExplosion(e) => Attribute::ExtendedExplosion(ExtendedExplosion {
+                explosion: (&e.explosion).into(),
+                unknown1: e.unknown1,
+                secondary_actor: actor(e.secondary_actor),
+            }),
+            A::FlaggedByte(flag, byte) => Attribute::FlaggedByte(FlaggedByte {
+                flag: *flag,
+                byte: *byte,
+            }),
+            A::ActiveActor(a) => Attribute::ActiveActor(a.into()),
+            A::Float(f) => Attribute::Float(*f),
+            A::GameMode(bits, mode) => Attribute::GameMode(GameMode {
+                bits: *bits,
+                mode: *mode,
+            }),
+            A::Int(i) => Attribute::Int(*i),
+            A::Int64(i) => Attribute::Int64(i.to_string()),
+            A::Loadout(l) => Attribute::Loadout(l.as_ref().into()),
+            A::TeamLoadout(l) => Attribute::TeamLoadout(TeamLoadout {
+                blue: (&l.blue).into(),
+                orange: (&l.orange).into(),
+            }),
+            A::Location(l) => Attribute::Location(l.into()),
+            A::MusicStinger(m) => Attribute::MusicStinger(MusicStinger {
+                flag: m.flag,
+                cue: m.cue,
+                trigger: m.trigger,
+            }),
+            A::PlayerHistoryKey(k) => Attribute::PlayerHistoryKey(*k),
+            A::Pickup(p) => Attribute::Pickup(Pickup {
+                instigator: p.instigator.map(actor),
+                picked_up: p.picked_up,
+            }),
+            A::PickupNew(p) => Attribute::PickupNew(PickupNew {
+                instigator: p.instigator.map(actor),
+                picked_up: u32::from(p.picked_up),
+            }),
+            A::QWord(q) => Attribute::QWord(q.to_string()),
+            A::Welded(w) => Attribute::Welded(Welded {
+                active: w.active,
+                actor: actor(w.actor),
+                offset: (&w.offset).into(),
+                mass: w.mass,
+                rotation: (&w.rotation).into(),
+            }),
+            A::Title(
+                unknown1,
+                unknown2,
+                unknown3,
+                unknown4,
+                unknown5,
+                unknown6,
+                unknown7,
+                unknown8,
+            ) => Attribute::Title(Title {
+                unknown1: *unknown1,
+                unknown2: *unknown2,
+                unknown3: *unknown3,
+                unknown4: *unknown4,
+                unknown5: *unknown5,
+                unknown6: *unknown6,
+                unknown7: *unknown7,
+                unknown8: *unknown8,
+            }),
+            A::TeamPaint(p) => Attribute::TeamPaint(TeamPaint {
+                team: u32::from(p.team),
+                primary_color: u32::from(p.primary_color),
+                accent_color: u32::from(p.accent_color),
+                primary_finish: p.primary_finish,
+                accent_finish: p.accent_finish,
+            }),
+            A::RigidBody(rb) => Attribute::RigidBody(RigidBody {
+                sleeping: rb.sleeping,
+                location: (&rb.location).into(),
+                rotation: (&rb.rotation).into(),
+                linear_velocity: rb.linear_velocity.as_ref().map(Vector3::from),
+                angular_velocity: rb.angular_velocity.as_ref().map(Vector3::from),
+            }),
+            A::String(s) => Attribute::String(s.clone()),
+            A::UniqueId(id) => Attribute::UniqueId(id.as_ref().into()),
+            A::Reservation(r) => Attribute::Reservation(Reservation {
+                number: r.number,
+                unique_id: (&r.unique_id).into(),
+                name: r.name.clone(),
+                unknown1: r.unknown1,
+                unknown2: r.unknown2,
+                unknown3: r.unknown3,
+            }),
+            A::PartyLeader(id) => Attribute::PartyLeader(id.as_ref().map(|id| id.as_ref().into())),
+            A::PrivateMatch(p) => Attribute::PrivateMatch(PrivateMatchSettings {
+                mutators: p.mutators.clone(),
+                joinable_by: p.joinable_by,
+                max_players: p.max_players,
+                game_name: p.game_name.clone(),
+                password: p.password.clone(),
+                flag: p.flag,
+            }),
+            A::LoadoutOnline(slots) => Attribute::LoadoutOnline(loadout_values(slots)),
+            A::LoadoutsOnline(l) => Attribute::LoadoutsOnline(LoadoutsOnline {
+                blue: loadout_values(&l.blue),
+                orange: loadout_values(&l.orange),
+                unknown1: l.unknown1,
+                unknown2: l.unknown2,
+            }),
+            A::StatEvent(s) => Attribute::StatEvent(StatEvent {
+                unknown1: s.unknown1,
+                object_id: s.object_id,
+            }),
+            A::Rotation(r) => Attribute::Rotation(r.into()),
+            A::RepStatTitle(t) => Attribute::RepStatTitle(RepStatTitle {
+                unknown: t.unknown,
+                name: t.name.clone(),
+                unknown2: t.unknown2,
+                index: t.index,
+                value: t.value,
+            }),
+            A::PickupInfo(p) => Attribute::PickupInfo(PickupInfo {
+                active: p.active,
+                actor: actor(p.actor),
+                items_are_preview: p.items_are_preview,
+                unknown: p.unknown,
+                unknown2: p.unknown2,
+            }),
+            A::Impulse(i) => Attribute::Impulse(Impulse {
+                compressed_rotation: i.compressed_rotation,
+                speed: i.speed,
+            }),
+            A::ReplicatedBoost(b) => Attribute::ReplicatedBoost(ReplicatedBoost {
+                grant_count: u32::from(b.grant_count),
+                boost_amount: u32::from(b.boost_amount),
+                unused1: u32::from(b.unused1),
+                unused2: u32::from(b.unused2),
+            }),
+            A::LogoData(l) => Attribute::LogoData(LogoData {
+                logo_id: l.logo_id,
+                swap_colors: l.swap_colors,
+            }),
+        }
+    }
+}
+
+impl From<&boxcars::ActiveActor> for ActiveActor {
+    fn from(a: &boxcars::ActiveActor) -> Self {
+        ActiveActor {
+            active: a.active,
+            actor: actor(a.actor),
+        }
+    }
+}
+
+impl From<&boxcars::Explosion> for Explosion {
+    fn from(e: &boxcars::Explosion) -> Self {
+        Explosion {
+            flag: e.flag,
+            actor: actor(e.actor),
+            location: (&e.location).into(),
+        }
+    }
+}
+
+impl From<&boxcars::Loadout> for LoadoutConfig {
+    fn from(l: &boxcars::Loadout) -> Self {
+        LoadoutConfig {
+            version: u32::from(l.version),
+            body: l.body,
+            decal: l.decal,
+            wheels: l.wheels,
+            rocket_trail: l.rocket_trail,
+            antenna: l.antenna,
+            topper: l.topper,
+            unknown1: l.unknown1,
+            unknown2: l.unknown2,
+            engine_audio: l.engine_audio,
+            trail: l.trail,
+            goal_explosion: l.goal_explosion,
+            banner: l.banner,
+            product_id: l.product_id,
+        }
+    }
+}
+
+impl From<&boxcars::ProductValue> for LoadoutDetail {
+    fn from(v: &boxcars::ProductValue) -> Self {
+        use boxcars::ProductValue as V;
+
+        match v {
+            V::NoColor => LoadoutDetail::NoColor,
+            V::Absent => LoadoutDetail::Absent,
+            V::OldColor(c) => LoadoutDetail::OldColor(*c),
+            V::NewColor(c) => LoadoutDetail::NewColor(*c),
+            V::OldPaint(p) => LoadoutDetail::OldPaint(*p),
+            V::NewPaint(p) => LoadoutDetail::NewPaint(*p),
+            V::Title(t) => LoadoutDetail::Title(t.clone()),
+            V::SpecialEdition(e) => LoadoutDetail::SpecialEdition(*e),
+            V::OldTeamEdition(e) => LoadoutDetail::OldTeamEdition(*e),
+            V::NewTeamEdition(e) => LoadoutDetail::NewTeamEdition(*e),
+        }
+    }
+}
+
+fn loadout_values(slots: &[Vec<boxcars::Product>]) -> Vec<Vec<LoadoutValue>> {
+    slots
+        .iter()
+        .map(|products| {
+            products
+                .iter()
+                .map(|p| LoadoutValue {
+                    unknown: p.unknown,
+                    object_ind: p.object_ind.0 as u32,
+                    value: (&p.value).into(),
+                })
+                .collect()
+        })
+        .collect()
+}
@@ -0,0 +1,84 @@
+use std::collections::HashMap;
+use std::sync::LazyLock;
+
+/// Lowercase map codes to display names and variants
+pub static MAP_NAMES: LazyLock<HashMap<&str, (&str, Option<&str>)>> = LazyLock::new(|| {
+    HashMap::from([
+        ("stadium_p", ("DFH Stadium", None)),
+        ("stadium_day_p", ("DFH Stadium", Some("Day"))),
+        ("stadium_foggy_p", ("DFH Stadium", Some("Stormy"))),
+        ("stadium_winter_p", ("DFH Stadium", Some("Snowy"))),
+        ("stadium_race_day_p", ("DFH Stadium", Some("Circuit"))),
+        ("stadium_10a_p", ("DFH Stadium", Some("10th Anniversary"))),
+        ("eurostadium_p", ("Mannfield", None)),
+        ("eurostadium_night_p", ("Mannfield", Some("Night"))),
+        ("eurostadium_rainy_p", ("Mannfield", Some("Stormy"))),
+        ("eurostadium_snownight_p", ("Mannfield", Some("Snowy"))),
+        ("eurostadium_dusk_p", ("Mannfield", Some("Dusk"))),
+        ("park_p", ("Beckwith Park", None)),
+        ("park_night_p", ("Beckwith Park", Some("Midnight"))),
+        ("park_rainy_p", ("Beckwith Park", Some("Stormy"))),
+        ("park_snowy_p", ("Beckwith Park", Some("Snowy"))),
+        ("park_bman_p", ("Beckwith Park", Some("Gotham Night"))),
+        ("trainstation_p", ("Urban Central", None)),
+        ("trainstation_night_p", ("Urban Central", Some("Night"))),
+        ("trainstation_dawn_p", ("Urban Central", Some("Dawn"))),
+        ("trainstation_spooky_p", ("Urban Central", Some("Haunted"))),
+        ("haunted_trainstation_p", ("Urban Central", Some("Haunted"))),
+        ("utopiastadium_p", ("Utopia Coliseum", None)),
+        ("utopiastadium_dusk_p", ("Utopia Coliseum", Some("Dusk"))),
+        ("utopiastadium_snow_p", ("Utopia Coliseum", Some("Snowy"))),
+        ("utopiastadium_lux_p", ("Utopia Coliseum", Some("Gilded"))),
+        ("cs_p", ("Champions Field", None)),
+        ("cs_day_p", ("Champions Field", Some("Day"))),
+        ("swoosh_p", ("Champions Field", Some("Nike FC"))),
+        ("cs_hw_p", ("Rivals Arena", None)),
+        ("arc_p", ("Starbase ARC", None)),
+        ("arc_standard_p", ("Starbase ARC", Some("Standard"))),
+        ("arc_darc_p", ("Starbase ARC", Some("Aftermath"))),
+        ("wasteland_p", ("Wasteland", None)),
+        ("wasteland_night_p", ("Wasteland", Some("Night"))),
+        ("wasteland_s_p", ("Wasteland", Some("Standard"))),
+        (
+            "wasteland_night_s_p",
+            ("Wasteland", Some("Standard, Night")),
+        ),
+        ("wasteland_grs_p", ("Wasteland", Some("Pitched"))),
+        ("neotokyo_p", ("Neo Tokyo", None)),
+        ("neotokyo_standard_p", ("Neo Tokyo", Some("Standard"))),
+        ("neotokyo_toon_p", ("Neo Tokyo", Some("Comic"))),
+        ("neotokyo_arcade_p", ("Neo Tokyo", Some("Arcade"))),
+        ("neotokyo_hax_p", ("Neo Tokyo", Some("Hacked"))),
+        ("underwater_p", ("AquaDome", None)),
+        ("underwater_grs_p", ("AquaDome", Some("Salty Shallows"))),
+        ("farm_p", ("Farmstead", None)),
+        ("farm_night_p", ("Farmstead", Some("Night"))),
+        ("farm_upsidedown_p", ("Farmstead", Some("The Upside Down"))),
+        ("farm_grs_p", ("Farmstead", Some("Pitched"))),
+        ("farm_hw_p", ("Farmstead", Some("Spooky"))),
+        ("beach_p", ("Salty Shores", None)),
+        ("beach_night_p", ("Salty Shores", Some("Night"))),
+        ("beach_night_grs_p", ("Salty Shores", Some("Salty Fest"))),
+        ("music_p", ("Neon Fields", None)),
+        ("chn_stadium_p", ("Forbidden Temple", None)),
+        ("chn_stadium_day_p", ("Forbidden Temple", Some("Day"))),
+        ("fni_stadium_p", ("Forbidden Temple", Some("Fire & Ice"))),
+        ("street_p", ("Sovereign Heights", None)),
+        ("outlaw_p", ("Deadeye Canyon", None)),
+        ("outlaw_oasis_p", ("Deadeye Canyon", Some("Oasis"))),
+        ("woods_p", ("Drift Woods", None)),
+        ("woods_night_p", ("Drift Woods", Some("Night"))),
+        ("ff_dusk_p", ("Estadio Vida", Some("Dusk"))),
+        ("throwbackstadium_p", ("Throwback Stadium", None)),
+        ("throwbackhockey_p", ("Throwback Stadium", Some("Snowy"))),
+        ("hoopsstadium_p", ("Dunk House", None)),
+        ("hoopsstreet_p", ("The Block", None)),
+        ("shattershot_p", ("Core 707", None)),
+        ("labs_circlepillars_p", ("Pillars", None)),
+        ("labs_cosmic_v4_p", ("Cosmic", None)),
+        ("labs_doublegoal_v2_p", ("Double Goal", None)),
+        ("labs_octagon_02_p", ("Octagon", None)),
+        ("labs_underpass_p", ("Underpass", None)),
+        ("labs_utopia_p", ("Utopia Retro", None)),
+    ])
+});
@@ -6,11 +6,16 @@ pub mod properties;
 pub mod root;
 
 pub use ballchasing::{
-    BallchasingPlayer, BallchasingReplay, BallchasingTeam, BallchasingTeamStats, BoostStats,
-    CoreStats, DemoStats, PlayerStats,
+    BallStats, BallchasingPlayer, BallchasingReplay, BallchasingTeam, BallchasingTeamStats,
+    BoostStats, CameraSettings, CameraStats, CarPaint, CoreStats, DemoStats, GoalEvent,
+    LoadoutItem, MovementStats, PlayerBallStats, PlayerCamera, PlayerLoadout, PlayerStats,
+    PositioningStats, ProductAttribute, SameMatchReplay, Scoreline, UnresolvedValue,
 };
 pub use common::*;
 pub use frames::*;
 pub use player::PlayerStat;
 pub use properties::*;
-pub mod cars;
+pub use root::Root;
+pub mod maps;
+pub mod paints;
+pub mod playlists;
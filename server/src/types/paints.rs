@@ -0,0 +1,26 @@
+use std::collections::HashMap;
+use std::sync::LazyLock;
+
+/// Painted attribute IDs to paint names
+pub static PAINTS: LazyLock<HashMap<u32, &str>> = LazyLock::new(|| {
+    HashMap::from([
+        (1, "Crimson"),
+        (2, "Lime"),
+        (3, "Black"),
+        (4, "Sky Blue"),
+        (5, "Cobalt"),
+        (6, "Burnt Sienna"),
+        (7, "Forest Green"),
+        (8, "Purple"),
+        (9, "Pink"),
+        (10, "Orange"),
+        (11, "Grey"),
+        (12, "Titanium White"),
+        (13, "Saffron"),
+        (14, "Gold"),
+        (15, "Rose Gold"),
+        (16, "White Gold"),
+        (17, "Onyx"),
+        (18, "Platinum"),
+    ])
+});
@@ -1,8 +1,10 @@
+use schemars::JsonSchema;
 use serde::Serialize;
 
-#[derive(Debug, Serialize)]
+#[derive(Debug, Serialize, JsonSchema)]
 pub struct PlayerStat {
-    pub player_id: PlayerId,
+    /// Not recorded by older replays
+    pub player_id: Option<PlayerId>,
     pub name: String,
     pub platform: PlatformValue,
     pub online_id: String,
@@ -15,51 +17,53 @@ pub struct PlayerStat {
     pub b_bot: bool,
 }
 
-#[derive(Debug, Serialize)]
+#[derive(Debug, Serialize, JsonSchema)]
+#[schemars(rename = "HeaderPlayerId")]
 pub struct PlayerId {
     pub name: String,
     pub fields: PlayerFields,
 }
 
-#[derive(Debug, Serialize)]
+#[derive(Debug, Serialize, JsonSchema)]
 pub struct PlayerFields {
     pub uid: String,
-    pub np_id: NpId,
-    pub epic_account_id: String,
+    /// PlayStation players only
+    pub np_id: Option<NpId>,
+    pub epic_account_id: Option<String>,
     pub platform: Platform,
 }
 
-#[derive(Debug, Serialize)]
+#[derive(Debug, Serialize, JsonSchema)]
 pub struct NpId {
     pub name: String,
     pub fields: NpFields,
 }
 
-#[derive(Debug, Serialize)]
+#[derive(Debug, Serialize, JsonSchema)]
 pub struct NpFields {
     pub handle: Handle,
     pub opt: String,
     pub reserved: String,
 }
 
-#[derive(Debug, Serialize)]
+#[derive(Debug, Serialize, JsonSchema)]
 pub struct Handle {
     pub name: String,
     pub fields: HandleFields,
 }
 
-#[derive(Debug, Serialize)]
+#[derive(Debug, Serialize, JsonSchema)]
 pub struct HandleFields {
     pub data: String,
 }
 
-#[derive(Debug, Serialize)]
+#[derive(Debug, Serialize, JsonSchema)]
 pub struct Platform {
     pub kind: String,
     pub value: String,
 }
 
-#[derive(Debug, Serialize)]
+#[derive(Debug, Serialize, JsonSchema)]
 pub struct PlatformValue {
     pub kind: String,
     pub value: String,
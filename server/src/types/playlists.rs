@@ -0,0 +1,62 @@
+use std::collections::HashMap;
+use std::sync::LazyLock;
+
+/// Playlist IDs (`ProjectX.GRI_X:ReplicatedGamePlaylist`) to
+/// Ballchasing-style playlist IDs and display names
+pub static PLAYLISTS: LazyLock<HashMap<i32, (&str, &str)>> = LazyLock::new(|| {
+    HashMap::from([
+        (1, ("unranked-duels", "Duel")),
+        (2, ("unranked-doubles", "Doubles")),
+        (3, ("unranked-standard", "Standard")),
+        (4, ("unranked-chaos", "Chaos")),
+        (6, ("private", "Private")),
+        (7, ("season", "Season")),
+        (8, ("offline", "Offline Splitscreen")),
+        (9, ("training", "Training")),
+        (10, ("ranked-duels", "Ranked Duel")),
+        (11, ("ranked-doubles", "Ranked Doubles")),
+        (12, ("ranked-solo-standard", "Ranked Solo Standard")),
+        (13, ("ranked-standard", "Ranked Standard")),
+        (15, ("snowday", "Snow Day")),
+        (16, ("rocketlabs", "Rocket Labs")),
+        (17, ("hoops", "Hoops")),
+        (18, ("rumble", "Rumble")),
+        (19, ("workshop", "Workshop")),
+        (22, ("tournament", "Tournament")),
+        (23, ("dropshot", "Dropshot")),
+        (24, ("local", "Local Match")),
+        (27, ("ranked-hoops", "Ranked Hoops")),
+        (28, ("ranked-rumble", "Ranked Rumble")),
+        (29, ("ranked-dropshot", "Ranked Dropshot")),
+        (30, ("ranked-snowday", "Ranked Snow Day")),
+        (31, ("ghost-hunt", "Ghost Hunt")),
+        (32, ("beach-ball", "Beach Ball")),
+        (33, ("spike-rush", "Spike Rush")),
+        (34, ("tournament", "Tournament")),
+        (35, ("rocketlabs", "Rocket Labs")),
+        (37, ("dropshot-rumble", "Dropshot Rumble")),
+        (38, ("heatseeker", "Heatseeker")),
+        (41, ("boomer-ball", "Boomer Ball")),
+        (43, ("heatseeker-doubles", "Heatseeker Doubles")),
+        (44, ("winter-breakaway", "Winter Breakaway")),
+        (46, ("gridiron", "Gridiron")),
+        (47, ("super-cube", "Super Cube")),
+        (48, ("tactical-rumble", "Tactical Rumble")),
+        (49, ("spring-loaded", "Spring Loaded")),
+        (50, ("speed-demon", "Speed Demon")),
+        (52, ("gotham-city-rumble", "Gotham City Rumble")),
+        (54, ("knockout", "Knockout")),
+    ])
+});
+
+/// Playlist implied by the header `MatchType` alone
+pub fn match_type_playlist(match_type: &str) -> Option<(&'static str, &'static str)> {
+    match match_type {
+        "Private" => Some(("private", "Private")),
+        "Season" => Some(("season", "Season")),
+        "Offline" => Some(("offline", "Offline")),
+        "Tournament" => Some(("tournament", "Tournament")),
+        "Lan" => Some(("local", "Local Match")),
+        _ => None,
+    }
+}
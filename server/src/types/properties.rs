@@ -1,46 +1,50 @@
 use crate::types::PlayerStat;
+use schemars::JsonSchema;
 use serde::Serialize;
 
-#[derive(Debug, Serialize)]
+/// Typed replay header; built from the raw header properties with `Properties::try_from`.
+/// Optional fields are absent from older or unnamed replays.
+#[derive(Debug, Serialize, JsonSchema)]
 pub struct Properties {
     pub team_size: u8,
     pub team0_score: u8,
+    pub team1_score: u8,
     pub total_seconds_played: f32,
-    pub match_start_epoch: String,
+    pub match_start_epoch: Option<String>,
     pub goals: Vec<Goal>,
     pub highlights: Vec<Highlight>,
     pub player_stats: Vec<PlayerStat>,
-    pub replay_name: String,
+    pub replay_name: Option<String>,
     pub replay_version: u32,
-    pub replay_last_save_version: u32,
-    pub game_version: u32,
-    pub build_id: u32,
-    pub changelist: u32,
-    pub build_version: String,
-    pub reserve_megabytes: u32,
-    pub record_fps: u32,
-    pub keyframe_delay: u32,
+    pub replay_last_save_version: Option<u32>,
+    pub game_version: Option<u32>,
+    pub build_id: Option<i32>,
+    pub changelist: Option<u32>,
+    pub build_version: Option<String>,
+    pub reserve_megabytes: Option<u32>,
+    pub record_fps: f32,
+    pub keyframe_delay: f32,
     pub max_channels: u32,
-    pub max_replay_size_mb: u32,
+    pub max_replay_size_mb: Option<u32>,
     pub id: String,
-    pub match_guid: String,
+    pub match_guid: Option<String>,
     pub map_name: String,
     pub date: String,
     pub num_frames: u32,
     pub match_type: String,
 }
 
-#[derive(Debug, Serialize)]
+#[derive(Debug, Serialize, JsonSchema)]
 pub struct Goal {
     pub frame: u32,
     pub player_name: String,
     pub player_team: u8,
 }
 
-#[derive(Debug, Serialize)]
+#[derive(Debug, Serialize, JsonSchema)]
 pub struct Highlight {
     pub frame: u32,
     pub car_name: String,
     pub ball_name: String,
-    pub goal_actor_name: String,
+    pub goal_actor_name: Option<String>,
 }
@@ -1,21 +1,27 @@
 use super::*;
+use schemars::JsonSchema;
 use serde::Serialize;
+use serde_json::{Map, Value};
 
-#[derive(Debug, Serialize)]
+/// Owned mirror of `boxcars::Replay`; the JSON contract of `/v1/output`
+#[derive(Debug, Serialize, JsonSchema)]
 pub struct Root {
     pub header_size: usize,
     pub header_crc: u32,
     pub major_version: u16,
     pub minor_version: u16,
-    pub net_version: u32,
+    pub net_version: Option<u32>,
     pub game_type: String,
-    pub properties: Properties,
+    /// Typed header, `None` when it doesn't match the schema `/header` validates against
+    pub properties: Option<Properties>,
+    /// Every header property as boxcars reads it, including those `properties` leaves out
+    pub raw_properties: Map<String, Value>,
     pub content_size: usize,
     pub content_crc: u32,
     pub network_frames: Option<NetworkFrames>,
     pub levels: Vec<String>,
     pub keyframes: Vec<Keyframe>,
-    pub debug_info: Vec<String>, // Simplified
+    pub debug_info: Vec<DebugInfo>,
     pub tick_marks: Vec<TickMark>,
     pub packages: Vec<String>,
     pub objects: Vec<String>,
@@ -23,3 +29,37 @@ pub struct Root {
     pub class_indices: Vec<Index>,
     pub net_cache: Vec<NetCache>,
 }
+
+impl From<&boxcars::Replay> for Root {
+    fn from(replay: &boxcars::Replay) -> Self {
+        Root {
+            header_size: replay.header_size as usize,
+            header_crc: replay.header_crc,
+            major_version: replay.major_version as u16,
+            minor_version: replay.minor_version as u16,
+            net_version: replay.net_version.map(|v| v as u32),
+            game_type: replay.game_type.clone(),
+            properties: replay.properties.as_slice().try_into().ok(),
+            raw_properties: replay
+                .properties
+                .iter()
+                .map(|(key, prop)| {
+                    let value = serde_json::to_value(prop).unwrap_or(Value::Null);
+                    (key.clone(), value)
+                })
+                .collect(),
+            content_size: replay.content_size as usize,
+            content_crc: replay.content_crc,
+            network_frames: replay.network_frames.as_ref().map(NetworkFrames::from),
+            levels: replay.levels.clone(),
+            keyframes: replay.keyframes.iter().map(Keyframe::from).collect(),
+            debug_info: replay.debug_info.iter().map(DebugInfo::from).collect(),
+            tick_marks: replay.tick_marks.iter().map(TickMark::from).collect(),
+            packages: replay.packages.clone(),
+            objects: replay.objects.clone(),
+            names: replay.names.clone(),
+            class_indices: replay.class_indices.iter().map(Index::from).collect(),
+            net_cache: replay.net_cache.iter().map(NetCache::from).collect(),
+        }
+    }
+}
@@ -0,0 +1,146 @@
+// Generated from server/src/types by `UPDATE_SCHEMA=1 cargo test`. Do not edit.
+
+export type BallchasingReplay = { id: string, created: string, status: string, rocket_league_id: string, match_guid: string, title: string, map_code: string, match_type: string, team_size: number, 
+/**
+ * Ballchasing-style playlist, e.g. `ranked-doubles`; `None` when it can't be told
+ */
+playlist_id: string | null, duration: number, overtime: boolean, overtime_seconds: number, 
+/**
+ * Header `Date` as RFC 3339; empty when it can't be parsed
+ */
+date: string, 
+/**
+ * Whether `date` carries the real offset rather than the recorder's clock labelled as UTC
+ */
+date_has_timezone: boolean, blue: BallchasingTeam, orange: BallchasingTeam, playlist_name: string | null, 
+/**
+ * Display name including the variant, e.g. `Mannfield (Night)`; `None` for unknown codes
+ */
+map_name: string | null, 
+/**
+ * Variant of the map, e.g. `Night` or `Snowy`
+ */
+map_variant: string | null, goals: Array<GoalEvent>, 
+/**
+ * SHA-256 of the uploaded file
+ */
+content_hash: string, 
+/**
+ * Player who saved the replay
+ */
+recorded_by: string | null, 
+/**
+ * Other recordings of the same match (same `match_guid`) this server has parsed. Only
+ * the last `MATCH_REGISTRY_SIZE` matches are remembered, and only since startup
+ * unless `CACHE_DIR` is set
+ */
+same_match: Array<SameMatchReplay>, 
+/**
+ * Header or network values the lookup tables don't know
+ */
+unresolved: Array<UnresolvedValue>, };
+
+export type BatchResult = { "status": "ok", replay: BallchasingReplay, } | { "status": "error", error: string, 
+/**
+ * Same machine code as the single-file routes
+ */
+code: string, };
+
+export type Job = { id: string, status: JobStatus, file_name: string | null, callback_url: string | null, 
+/**
+ * `None` without a callback URL
+ */
+callback_status: CallbackStatus | null, created_at: string, updated_at: string, error: JobError | null, };
+
+export type JobReport = { result: BallchasingReplay | null, id: string, status: JobStatus, file_name: string | null, callback_url: string | null, 
+/**
+ * `None` without a callback URL
+ */
+callback_status: CallbackStatus | null, created_at: string, updated_at: string, error: JobError | null, };
+
+export type JobStatus = "queued" | "running" | "done" | "failed";
+
+export type CallbackStatus = "pending" | "delivered" | "failed";
+
+export type JobError = { error: string, code: string, };
+
+export type BallchasingTeam = { color: string, name: string, players: Array<BallchasingPlayer>, stats: BallchasingTeamStats, };
+
+export type BallchasingPlayer = { name: string, id: PlayerId, car_id: number, car_name: string, loadout: PlayerLoadout, camera: CameraSettings | null, stats: PlayerStats, };
+
+export type PlayerId = { platform: string, id: string, };
+
+export type CameraSettings = { fov: number, height: number, pitch: number, distance: number, stiffness: number, swivel_speed: number, 
+/**
+ * Only replicated by newer replays
+ */
+transition_speed: number | null, };
+
+export type PlayerLoadout = { body: LoadoutItem | null, decal: LoadoutItem | null, wheels: LoadoutItem | null, boost: LoadoutItem | null, antenna: LoadoutItem | null, topper: LoadoutItem | null, engine_audio: LoadoutItem | null, trail: LoadoutItem | null, goal_explosion: LoadoutItem | null, banner: LoadoutItem | null, paint: CarPaint | null, };
+
+export type LoadoutItem = { id: number, name: string | null, attributes: Array<ProductAttribute>, };
+
+export type ProductAttribute = { 
+/**
+ * `painted`, `special_edition`, `team_edition`, `color`, `title`, `certified`, or
+ * the attribute class for other kinds the replay doesn't carry a value for
+ */
+kind: string, id: number | null, name: string | null, };
+
+export type CarPaint = { 
+/**
+ * Index into the team's primary color swatches
+ */
+primary_color: number, 
+/**
+ * Index into the accent color swatches
+ */
+accent_color: number, primary_finish: LoadoutItem, accent_finish: LoadoutItem, };
+
+export type PlayerStats = { ball: PlayerBallStats, core: CoreStats, boost: BoostStats, movement: MovementStats, positioning: PositioningStats, demo: DemoStats, camera: CameraStats, };
+
+export type BallchasingTeamStats = { ball: BallStats, core: CoreStats, boost: BoostStats, movement: MovementStats, positioning: PositioningStats, demo: DemoStats, };
+
+export type PlayerBallStats = { possession_time: number, };
+
+export type BallStats = { possession_time: number, time_in_side: number, };
+
+export type CoreStats = { shots: number, shots_against: number, goals: number, goals_against: number, saves: number, assists: number, score: number, mvp: boolean, shooting_percentage: number, };
+
+export type BoostStats = { bpm: number, bcpm: number, avg_amount: number, amount_collected: number, amount_stolen: number, amount_collected_big: number, amount_stolen_big: number, amount_collected_small: number, amount_stolen_small: number, count_collected_big: number, count_stolen_big: number, count_collected_small: number, count_stolen_small: number, amount_overfill: number, amount_overfill_stolen: number, amount_used_while_supersonic: number, time_zero_boost: number, percent_zero_boost: number, time_full_boost: number, percent_full_boost: number, time_boost_0_25: number, time_boost_25_50: number, time_boost_50_75: number, time_boost_75_100: number, percent_boost_0_25: number, percent_boost_25_50: number, percent_boost_50_75: number, percent_boost_75_100: number, };
+
+export type MovementStats = { avg_speed: number, total_distance: number, time_supersonic_speed: number, time_boost_speed: number, time_slow_speed: number, time_ground: number, time_low_air: number, time_high_air: number, time_powerslide: number, count_powerslide: number, avg_powerslide_duration: number, avg_speed_percentage: number, percent_slow_speed: number, percent_boost_speed: number, percent_supersonic_speed: number, percent_ground: number, percent_low_air: number, percent_high_air: number, };
+
+export type PositioningStats = { avg_distance_to_ball: number, avg_distance_to_ball_possession: number, avg_distance_to_ball_no_possession: number, avg_distance_to_mates: number, time_defensive_third: number, time_neutral_third: number, time_offensive_third: number, time_defensive_half: number, time_offensive_half: number, time_behind_ball: number, time_infront_ball: number, time_most_back: number, time_most_forward: number, time_closest_to_ball: number, time_farthest_from_ball: number, percent_defensive_third: number, percent_offensive_third: number, percent_neutral_third: number, percent_defensive_half: number, percent_offensive_half: number, percent_behind_ball: number, percent_infront_ball: number, percent_most_back: number, percent_most_forward: number, percent_closest_to_ball: number, percent_farthest_from_ball: number, goals_against_while_last_defender: number, };
+
+export type DemoStats = { inflicted: number, taken: number, };
+
+export type CameraStats = { time_ball_cam: number, percent_ball_cam: number, count_ball_cam_toggles: number, time_behind_view: number, percent_behind_view: number, };
+
+export type PlayerCamera = { name: string, id: PlayerId, team: string, camera: CameraSettings | null, stats: CameraStats, };
+
+export type SameMatchReplay = { id: string, recorded_by: string | null, };
+
+export type UnresolvedValue = { field: string, value: string, };
+
+export type GoalEvent = { frame: number, 
+/**
+ * Replay time (seconds) of the goal frame
+ */
+time: number, 
+/**
+ * Game clock at the moment of the goal; stays at 0 during overtime
+ */
+seconds_remaining: number | null, overtime: boolean, team: string, scorer: string, assister: string | null, 
+/**
+ * Score after this goal
+ */
+score: Scoreline, 
+/**
+ * Ball speed in km/h as it crossed the line
+ */
+ball_speed: number, ball_location: Location | null, };
+
+export type Scoreline = { blue: number, orange: number, };
+
+export type Location = { x: number, y: number, z: number, };